@@ -0,0 +1,49 @@
+//! Throughput of the velocity and position update
+//!
+//! Compare the scalar update with the vectorized kernel by running
+//!
+//! ```text
+//! cargo bench --bench velocity_update
+//! cargo bench --bench velocity_update --features simd
+//! ```
+
+use pso_rs::*;
+use std::time::Instant;
+
+/// Constant objective, so that the iterations are dominated by the particle updates
+fn flat(_p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+    0.0
+}
+
+fn main() {
+    let kernel = if cfg!(feature = "simd") {
+        "vectorized"
+    } else {
+        "scalar"
+    };
+    for (population_size, flat_dim) in [(1000, 30), (100, 300), (100, 3000)] {
+        let config = Config {
+            dimensions: vec![flat_dim],
+            bounds: vec![(-5.0, 5.0); flat_dim],
+            population_size,
+            neighborhood_type: NeighborhoodType::Gbest,
+            progress_bar: false,
+            parallelize: false,
+            seed: Some(20),
+            ..Config::default()
+        };
+        let mut pso = pso_rs::init(config, flat).unwrap();
+        let iterations = 100_000_000 / (population_size * flat_dim);
+        let start = Instant::now();
+        for _ in 0..iterations {
+            pso.step().unwrap();
+        }
+        let elapsed = start.elapsed();
+        let throughput =
+            (iterations * population_size * flat_dim) as f64 / elapsed.as_secs_f64() / 1e6;
+        println!(
+            "{} kernel, {} particles x {} coefficients: {:.1} M coefficient updates/s",
+            kernel, population_size, flat_dim, throughput
+        );
+    }
+}
@@ -1,7 +1,6 @@
 use pso_rs::*;
 use std::process;
-const N_PARTICLES:usize = 20;
-
+const N_PARTICLES: usize = 20;
 
 fn main() {
     let dimensions = vec![N_PARTICLES, 3];
@@ -58,7 +57,7 @@ fn main() {
 }
 
 /// Get Euclidian distance of two particles
-fn l2(x_i: Particle, x_j: Particle, particle_dim: usize) -> f64 {
+fn l2(x_i: &Particle, x_j: &Particle, particle_dim: usize) -> f64 {
     // calculated as the square root of the sum of the squared vector values
     let mut sum: f64 = 0.0;
     for i in 0..particle_dim {
@@ -68,12 +67,13 @@ fn l2(x_i: Particle, x_j: Particle, particle_dim: usize) -> f64 {
 }
 
 /// Get potential energy of two particles
-fn v_ij(x_i: Particle, x_j: Particle, particle_dim: usize) -> f64 {
+fn v_ij(x_i: &Particle, x_j: &Particle, particle_dim: usize) -> f64 {
     let denom: f64 = 1.0 / l2(x_i, x_j, particle_dim);
     denom.powf(12.0) - denom.powf(6.0)
 }
 
 /// Get potential energy of a cluster of particles
+#[allow(clippy::ptr_arg)]
 fn e_lj(particle: &Particle, _flat_dim: usize, particle_dims: &Vec<usize>) -> f64 {
     let mut sum = 0.0;
     for i in 0..particle_dims[0] - 1 {
@@ -81,8 +81,8 @@ fn e_lj(particle: &Particle, _flat_dim: usize, particle_dims: &Vec<usize>) -> f6
             let true_i = i * particle_dims[1];
             let true_j = j * particle_dims[1];
             sum += v_ij(
-                particle[true_i..true_i + particle_dims[1]].to_vec(),
-                particle[true_j..true_j + particle_dims[1]].to_vec(),
+                &particle[true_i..true_i + particle_dims[1]],
+                &particle[true_j..true_j + particle_dims[1]],
                 particle_dims[1],
             );
         }
@@ -90,7 +90,7 @@ fn e_lj(particle: &Particle, _flat_dim: usize, particle_dims: &Vec<usize>) -> f6
     4.0 * sum
 }
 
-fn reshape(particle: &Particle, particle_dims: &Vec<usize>) -> Vec<Vec<f64>> {
+fn reshape(particle: &[f64], particle_dims: &[usize]) -> Vec<Vec<f64>> {
     // reshape particle
     let mut reshaped_cluster = vec![];
     let mut i = 0;
@@ -1,6 +1,6 @@
 use pso_rs::*;
 
-const N_DIMENSIONS:usize = 3;
+const N_DIMENSIONS: usize = 3;
 
 fn main() {
     let config = Config {
@@ -12,13 +12,19 @@ fn main() {
     };
     use std::time::Instant;
     let before = Instant::now();
-    let pso = pso_rs::run(config, sum_squares, Some(|f_best| f_best < 1e-4)).unwrap();
+    let pso = pso_rs::run(
+        config,
+        sum_squares,
+        Some(Box::new(|state: &RunState| state.f_best < 1e-4)),
+    )
+    .unwrap();
     println!("Elapsed time: {:.2?}", before.elapsed());
     let model = pso.model;
     println!("Found minimum: {:#?} ", model.get_f_best());
     println!("Found minimizer: {:#?} ", model.get_x_best());
 }
 
+#[allow(clippy::ptr_arg)]
 fn sum_squares(p: &Particle, _flat_dim: usize, dimensions: &Vec<usize>) -> f64 {
     (0..dimensions[0]).map(|i| i as f64 * p[i].powf(2.0)).sum()
 }
@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error type for all fallible operations
+#[derive(Debug)]
+pub enum PsoError {
+    /// A `Config` field has an invalid value
+    InvalidConfig { field: &'static str, reason: String },
+    /// A particle coefficient became NaN, usually because of bad parameterization
+    NaNCoefficient { particle: usize, dimension: usize },
+    /// The objective function value of a particle is NaN or infinite, see [`NonFinitePolicy`](crate::NonFinitePolicy)
+    NonFiniteObjective { particle: usize, value: f64 },
+    /// The objective function failed to evaluate a particle
+    Objective {
+        particle: usize,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The API was used incorrectly, e.g. `tell` was called without `ask`
+    InvalidUsage(&'static str),
+    /// Reading or writing a file failed
+    Io(io::Error),
+    /// Serializing or deserializing a checkpoint or configuration failed
+    Serialization(Box<dyn Error + Send + Sync>),
+}
+
+impl PsoError {
+    pub(crate) fn invalid_config(field: &'static str, reason: impl Into<String>) -> PsoError {
+        PsoError::InvalidConfig {
+            field,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for PsoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PsoError::InvalidConfig { field, reason } => {
+                write!(f, "invalid config field `{}`: {}", field, reason)
+            }
+            PsoError::NaNCoefficient {
+                particle,
+                dimension,
+            } => write!(
+                f,
+                "coefficient {} of particle {} became NaN",
+                dimension, particle
+            ),
+            PsoError::NonFiniteObjective { particle, value } => write!(
+                f,
+                "objective function value of particle {} is {}",
+                particle, value
+            ),
+            PsoError::Objective { particle, source } => write!(
+                f,
+                "objective function failed for particle {}: {}",
+                particle, source
+            ),
+            PsoError::InvalidUsage(reason) => write!(f, "{}", reason),
+            PsoError::Io(err) => write!(f, "I/O error: {}", err),
+            PsoError::Serialization(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+impl Error for PsoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PsoError::Objective { source, .. } => Some(source.as_ref()),
+            PsoError::Io(err) => Some(err),
+            PsoError::Serialization(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PsoError {
+    fn from(err: io::Error) -> PsoError {
+        PsoError::Io(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<bincode::Error> for PsoError {
+    fn from(err: bincode::Error) -> PsoError {
+        PsoError::Serialization(err)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for PsoError {
+    fn from(err: serde_json::Error) -> PsoError {
+        PsoError::Serialization(Box::new(err))
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::de::Error> for PsoError {
+    fn from(err: toml::de::Error) -> PsoError {
+        PsoError::Serialization(Box::new(err))
+    }
+}
@@ -0,0 +1,112 @@
+use rand::distributions::uniform::SampleUniform;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+/// Floating point type of particle coefficients and objective function values
+///
+/// Implemented for `f64`, the default, and `f32`, which halves the memory used by the swarm
+pub trait Float:
+    Copy
+    + Debug
+    + Display
+    + PartialOrd
+    + Send
+    + Sync
+    + Sum
+    + SampleUniform
+    + MaybeSerde
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const INFINITY: Self;
+
+    /// Converts an `f64`, rounding to the nearest representable value
+    fn from_f64(x: f64) -> Self;
+    /// Converts to an `f64`
+    fn to_f64(self) -> f64;
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn floor(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn ln(self) -> Self;
+    fn cos(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+/// Serialization bounds of [`Float`], required only with the `serde` feature enabled
+#[cfg(feature = "serde")]
+pub trait MaybeSerde: Serialize + DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize + DeserializeOwned> MaybeSerde for T {}
+
+/// Serialization bounds of [`Float`], required only with the `serde` feature enabled
+#[cfg(not(feature = "serde"))]
+pub trait MaybeSerde {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> MaybeSerde for T {}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const INFINITY: Self = $t::INFINITY;
+
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn abs(self) -> Self {
+                $t::abs(self)
+            }
+            fn signum(self) -> Self {
+                $t::signum(self)
+            }
+            fn floor(self) -> Self {
+                $t::floor(self)
+            }
+            fn sqrt(self) -> Self {
+                $t::sqrt(self)
+            }
+            fn ln(self) -> Self {
+                $t::ln(self)
+            }
+            fn cos(self) -> Self {
+                $t::cos(self)
+            }
+            fn powi(self, n: i32) -> Self {
+                $t::powi(self, n)
+            }
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+            fn is_finite(self) -> bool {
+                $t::is_finite(self)
+            }
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                $t::total_cmp(self, other)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
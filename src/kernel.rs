@@ -0,0 +1,116 @@
+//! Vectorized velocity update kernel, used instead of the scalar update with the `simd` feature enabled
+//!
+//! The random coefficients of a particle are generated in a batch before the update, bounds are looked up per coefficient instead of with a modulo,
+//! and the update loop has no early returns, so that the compiler can vectorize it.
+
+use crate::error::PsoError;
+use crate::float::Float;
+use crate::model::Config;
+use rand::Rng;
+
+/// Coefficients of the constriction and inertia weight velocity updates
+pub(crate) struct Coefficients<F> {
+    pub chi: F,
+    pub w: F,
+    pub c1: F,
+    pub c2: F,
+    pub lr: F,
+    /// Lower end of the range of the random coefficients, whose upper end is `1`
+    pub r_min: F,
+}
+
+/// Buffers for the random coefficients of a particle, reused across the particles updated on the same thread
+pub(crate) struct Scratch<F> {
+    r: Vec<F>,
+    bits: Vec<u32>,
+}
+
+impl<F> Default for Scratch<F> {
+    fn default() -> Self {
+        Scratch {
+            r: vec![],
+            bits: vec![],
+        }
+    }
+}
+
+/// Returns the lower and upper bounds of each coefficient of the flattened particle
+pub(crate) fn flat_bounds<F: Float>(config: &Config<F>, flat_dim: usize) -> (Vec<F>, Vec<F>) {
+    (0..flat_dim).map(|j| config.coefficient_bounds(j)).unzip()
+}
+
+/// Fills `out` with numbers drawn uniformly from `low..high`
+///
+/// Each number is drawn from 32 random bits, half as many as `gen_range` uses for an `f64`, which leaves 24 bits of resolution
+fn fill_uniform<F: Float, R: Rng>(rng: &mut R, low: F, high: F, out: &mut [F], bits: &mut [u32]) {
+    rng.fill(bits);
+    let scale = high - low;
+    for (o, &b) in out.iter_mut().zip(bits.iter()) {
+        // the 24 most significant bits give a uniform number in 0..1
+        *o = low + scale * F::from_f64((b >> 8) as f64 * (1.0 / (1u32 << 24) as f64));
+    }
+}
+
+/// Updates the velocity and position of particle `i`, given its best position and its neighborhood best
+///
+/// Velocities are clamped to `v_max`, which is infinite for unclamped velocities. Positions are clamped to the bounds if `clamp` is set, and left for the caller to confine otherwise
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_particle<F: Float, R: Rng>(
+    i: usize,
+    particle: &mut [F],
+    velocity: &mut [F],
+    best: &[F],
+    lbest: &[F],
+    lower: &[F],
+    upper: &[F],
+    v_max: &[F],
+    coef: &Coefficients<F>,
+    clamp: bool,
+    rng: &mut R,
+    scratch: &mut Scratch<F>,
+) -> Result<(), PsoError> {
+    let n = particle.len();
+    // the buffers only allocate for the first particle of each thread
+    scratch.r.resize(2 * n, F::ZERO);
+    scratch.bits.resize(2 * n, 0);
+    fill_uniform(rng, coef.r_min, F::ONE, &mut scratch.r, &mut scratch.bits);
+    let (r1, r2) = scratch.r.split_at(n);
+    // equal lengths let the compiler elide bounds checks
+    let (velocity, best, lbest) = (&mut velocity[..n], &best[..n], &lbest[..n]);
+    let (lower, upper, v_max) = (&lower[..n], &upper[..n], &v_max[..n]);
+
+    for j in 0..n {
+        let x = particle[j];
+        let v = coef.chi
+            * (coef.w * velocity[j]
+                + coef.c1 * r1[j] * (best[j] - x)
+                + coef.c2 * r2[j] * (lbest[j] - x));
+        // comparisons are false for NaN, which is passed through as in the scalar update
+        let v = if v > v_max[j] {
+            v_max[j]
+        } else if v < -v_max[j] {
+            -v_max[j]
+        } else {
+            v
+        };
+        velocity[j] = v;
+        let x = x + coef.lr * v;
+        particle[j] = if !clamp {
+            x
+        } else if x > upper[j] {
+            upper[j]
+        } else if x < lower[j] {
+            lower[j]
+        } else {
+            x
+        };
+    }
+
+    match particle.iter().position(|x| x.is_nan()) {
+        Some(dimension) => Err(PsoError::NaNCoefficient {
+            particle: i,
+            dimension,
+        }),
+        None => Ok(()),
+    }
+}
@@ -32,8 +32,8 @@
 //! }
 //!
 //! // define a termination condition (optional)
-//! fn terminate(f_best: f64) -> bool {
-//!     f_best - (0.0) < 1e-4
+//! fn terminate(state: &RunState) -> bool {
+//!     state.f_best - (0.0) < 1e-4
 //! }
 //!
 //! let config = Config {
@@ -50,7 +50,7 @@
 //! let pso = pso_rs::run(
 //!     config,
 //!     objective_function,
-//!     Some(terminate)
+//!     Some(Box::new(terminate))
 //! ).unwrap();
 //!     
 //! let model = pso.model;
@@ -87,25 +87,228 @@
 //!     objective_function
 //! ).unwrap();
 //!
-//! // run PSO with no termination condition
-//! pso.run(|_| false);
+//! // run PSO until there is no improvement for 20 iterations,
+//! // or for at most one second
+//! pso.run(|state| {
+//!     let n = state.best_f_trajectory.len();
+//!     (n > 20 && state.best_f_trajectory[n - 21] <= state.f_best)
+//!         || state.elapsed.as_secs() >= 1
+//! }).unwrap();
 //!     
 //! let model = pso.model;
 //! println!("Found minimum: {:#?} ", model.get_f_best());
 //! println!("Minimizer: {:#?}", model.get_x_best());
 //! ```
 //!
+//! ## Use a closure as the objective function
+//!
+//! Any closure implementing `Fn(&Particle, usize, &Vec<usize>) -> f64 + Send + Sync` can be used as an objective function, so it can capture the data it needs:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! // data the objective function depends on
+//! let target = vec![0.5, -0.25];
+//!
+//! let objective_function = move |p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>| {
+//!     p.iter().zip(&target).map(|(x, t)| (x - t).powf(2.0)).sum::<f64>()
+//! };
+//!
+//! let config = Config {
+//!     dimensions: vec![2],
+//!     bounds: vec![(-1.0, 1.0); 2],
+//!     t_max: 10000,
+//!     ..Config::default()
+//! };
+//!
+//! let pso = pso_rs::run(config, objective_function, None).unwrap();
+//! println!("Found minimum: {:#?} ", pso.model.get_f_best());
+//! ```
+//!
+//! ## Objective functions that can fail
+//!
+//! Objective functions returning `Result<f64, E>` can be wrapped with `fallible`. Failed evaluations are retried, penalized, or abort the run with the error returned from `run`, depending on `objective_error_policy`:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! // objective function that can fail, e.g. because a solver does not converge
+//! fn objective_function(
+//!     p: &Particle,
+//!     _flat_dim: usize,
+//!     _dimensions: &Vec<usize>
+//! ) -> Result<f64, String> {
+//!     let r = p.iter().map(|x| x.powf(2.0)).sum::<f64>();
+//!     if r > 1.0 {
+//!         return Err(format!("solver did not converge for r = {}", r));
+//!     }
+//!     Ok(r)
+//! }
+//!
+//! let config = Config {
+//!     dimensions: vec![2],
+//!     bounds: vec![(-1.0, 1.0); 2],
+//!     t_max: 10000,
+//!     // failed evaluations get a large objective function value
+//!     objective_error_policy: ObjectiveErrorPolicy::Penalize(1e6),
+//!     ..Config::default()
+//! };
+//!
+//! let pso = pso_rs::run(config, fallible(objective_function), None).unwrap();
+//! println!("Found minimum: {:#?} ", pso.model.get_f_best());
+//! ```
+//!
+//! ## Step through the optimization
+//!
+//! `PSO::step` performs a single iteration and returns an `IterationReport`. `PSO` is also an `Iterator` over these reports, so the optimization can be interleaved with logging, UI updates or parameter changes:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! fn objective_function(
+//!     p: &Particle,
+//!     _flat_dim: usize,
+//!     _dimensions: &Vec<usize>
+//! ) -> f64 {
+//!     p.iter().map(|x| x.powf(2.0)).sum()
+//! }
+//!
+//! let config = Config {
+//!     dimensions: vec![2],
+//!     bounds: vec![(-5.0, 10.0); 2],
+//!     population_size: 20,
+//!     t_max: 2000,
+//!     progress_bar: false,
+//!     ..Config::default()
+//! };
+//!
+//! let mut pso = pso_rs::init(config, objective_function).unwrap();
+//!
+//! // a single iteration
+//! let report = pso.step().unwrap();
+//! println!("Iteration {}: {}", report.iteration, report.f_best);
+//!
+//! // the remaining iterations, until t_max is exceeded
+//! for report in &mut pso {
+//!     let report = report.unwrap();
+//!     if report.improved {
+//!         println!("Iteration {}: {}", report.iteration, report.f_best);
+//!     }
+//! }
+//! ```
+//!
+//! ## Evaluate the objective function externally
+//!
+//! If the objective function cannot be called from Rust, e.g. because it is computed by a separate simulation job, the positions to evaluate can be requested with `ask` and their objective function values fed back with `tell`:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! let config: Config = Config {
+//!     dimensions: vec![2],
+//!     bounds: vec![(-5.0, 10.0); 2],
+//!     population_size: 20,
+//!     ..Config::default()
+//! };
+//!
+//! let mut pso = pso_rs::init_ask_tell(config).unwrap();
+//!
+//! for _ in 0..100 {
+//!     // positions to evaluate in the next iteration
+//!     let positions = pso.ask().unwrap();
+//!     // evaluate them externally, e.g. in a separate simulation job
+//!     let scores = positions
+//!         .iter()
+//!         .map(|p| p[0].powf(2.0) + p[1].powf(2.0))
+//!         .collect();
+//!     pso.tell(scores).unwrap();
+//! }
+//!
+//! println!("Found minimum: {:#?} ", pso.model.get_f_best());
+//! println!("Minimizer: {:#?}", pso.model.get_x_best());
+//! ```
+//!
 //! # Notes
 //!
 //! ## Performance
 //!
-//! This implementation uses a flat vector (`Vec<f64>`) to represent any d-dimensional problem (see the [Optimization Problem Dimensionality](#optimization-problem-dimensionality) section). This means that the vector has an O(1) access time, and can be cached for fast access, similarly to a static array.
+//! This implementation uses a flat vector (`Vec<f64>`) to represent any d-dimensional problem (see the [Optimization Problem Dimensionality](#optimization-problem-dimensionality) section). This means that the vector has an O(1) access time, and can be cached for fast access, similarly to a static array. The positions, velocities and best found positions of all particles are each stored contiguously in a single `Population`, so the swarm does not allocate per particle and is traversed in memory order.
+//!
+//! The computation of the objective function for each particle is performed in parallel, as it is computationally expensive for any non-trivial problem. The velocity and position updates of the particles are also performed in parallel, each particle drawing from its own random number stream. In the future, complete swarms will be able to be run in parallel and optionally communicate their best found positions by passing messages.
+//!
+//! With the `simd` feature enabled, the velocity and position updates use a vectorized kernel, which draws the random coefficients of each particle in a batch and updates all coefficients without branching on the bounds. Seeded runs are reproducible with either kernel, but give different results with and without the feature. The throughput of both kernels can be compared with `cargo bench --bench velocity_update` and `cargo bench --bench velocity_update --features simd`.
+//!
+//! ## Velocity update
+//!
+//! By default, velocities are updated with Clerc's constriction factor, which requires `c1 + c2 > 4`. The classical inertia weight PSO of Shi and Eberhart can be used instead by setting `velocity_update: VelocityUpdate::InertiaWeight(w)`, where `w` is a constant, linearly decreasing or random `InertiaWeight`, e.g. with the common `c1 = c2 = 1.49445` and `InertiaWeight::Constant(0.7298)`. Velocities are clamped to `alpha` times the search range of each coefficient, `upper - lower`, and initial velocities are sampled within the same range; clamping can be disabled with `clamp_velocity: false`.
+//!
+//! For comparisons with the literature, `Config::spso2011()` returns the configuration of Standard PSO 2011 (SPSO 2011): 40 particles, a random topology where each particle informs 3 random particles, which is rewired whenever the best found value does not improve, and the rotation invariant velocity update, which samples the new position at random in a hypersphere around the center of gravity of the particle, its best position and its neighborhood best. Particles are updated synchronously, once per iteration.
+//!
+//! ## Neighborhood topology
+//!
+//! Each particle is attracted to the best position found in its neighborhood, set with `neighborhood_type`. Besides the ring (`Lbest`, of radius `rho`) and the whole swarm (`Gbest`), the Von Neumann grid (`VonNeumann`), the wheel around a hub particle (`Wheel`) and random topologies where each particle informs `k` random particles (`RandomK { k }`) are available. Any other topology can be given as the list of particles each particle sees with `NeighborhoodType::Custom`, or built edge by edge with a `NeighborhoodGraph`.
+//!
+//! The topology is static by default. With `dynamic_topology: DynamicTopology::RewireOnStagnation` it is rewired whenever the best found value does not improve, as in SPSO 2011, and with `DynamicTopology::Growing` the ring neighborhoods of `Lbest` grow over the run until every particle sees the whole swarm, trading early exploration for late exploitation.
+//!
+//! ## Boundary handling
+//!
+//! Particles leaving the `bounds` are handled according to `boundary_handling`. By default, coefficients are clamped to the bound they crossed (`BoundaryHandling::Clamp`), which keeps them moving into the wall, so particles tend to pile up on it. With `Reflect` they bounce back into the bounds with reversed velocity, with `Absorb` they stop at the bound, with `Random` they are sampled within the bounds again, and with `Periodic` they wrap around to the opposite bound, which suits angles and other periodic variables. With `LetFly`, particles are not confined at all, but are not evaluated while outside the bounds either, so they never become best found positions and return to the search space on their own.
+//!
+//! ## Initialization
+//!
+//! By default, the initial positions of the particles are sampled uniformly within the bounds. For expensive objective functions and small swarms, the search space can be covered more evenly with `initialization: Initialization::LatinHypercube`, which places exactly one particle in each of `population_size` slices of the range of every coefficient, or with the low-discrepancy `Halton` and `Sobol` sequences; Halton sequences lose their evenness beyond a few dozen coefficients, while Sobol sequences scale to many more. `OppositionBased` initialization also evaluates the opposite point of each uniformly sampled particle, reflected through the center of the bounds, and keeps the better half, at the cost of `population_size` extra evaluations.
+//!
+//! Known good solutions, e.g. the best cluster found by a previous run or a hand-designed configuration, can be given as the `initial_positions` of the first particles, one flattened position per particle. They are evaluated with the rest of the swarm and become the personal bests of their particles, while the remaining particles are sampled as usual. With `OppositionBased` initialization, only the sampled particles compete with their opposites.
+//!
+//! ## Single precision
+//!
+//! `Config`, `Model`, `PSO` and `Population` are generic over a `Float` type, `f64` by default. Swarms of `f32` use half the memory, which matters for very large swarms on memory-bound workloads; their objective functions take a `&Particle<f32>` and return an `f32`:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! fn objective_function(
+//!     p: &Particle<f32>,
+//!     _flat_dim: usize,
+//!     _dimensions: &Vec<usize>
+//! ) -> f32 {
+//!     p.iter().map(|x| x.powi(2)).sum()
+//! }
+//!
+//! let config = Config::<f32> {
+//!     dimensions: vec![2],
+//!     bounds: vec![(-5.0, 10.0); 2],
+//!     t_max: 10000,
+//!     ..Config::default()
+//! };
+//!
+//! let pso = pso_rs::run(config, objective_function, None).unwrap();
+//! println!("Found minimum: {:#?} ", pso.model.get_f_best());
+//! ```
+//!
+//! ## Reproducibility
+//!
+//! Setting `seed: Some(u64)` in the `Config` makes initialization and velocity updates deterministic, so runs with the same seed and configuration produce the same results, with or without `parallelize`.
+//!
+//! ## Checkpoints
+//!
+//! With the `serde` feature enabled, the complete state of a `PSO` instance can be saved with `PSO::save_checkpoint` and restored with `PSO::load_checkpoint`, so long runs can be resumed after an interruption.
+//!
+//! ## Configuration files
 //!
-//! The computation of the objective function for each particle is performed in parallel, as it is computationally expensive for any non-trivial problem. In the future, complete swarms will be able to be run in parallel and optionally communicate their best found positions by passing messages.
+//! With the `toml` or `json` features enabled, a `Config` can be loaded from a file with `Config::from_toml_file` or `Config::from_json_file`. Fields missing from the file are set to their default values, and the loaded configuration is validated before it is returned:
+//!
+//! ```toml
+//! dimensions = [20, 3]
+//! bounds = [[-2.5, 2.5], [-2.5, 2.5], [-2.5, 2.5]]
+//! population_size = 10
+//! neighborhood_type = "Lbest"
+//! seed = 42
+//! ```
 //!
 //! ## Optimization problem dimensionality
 //!
-//! Even though you can have particles of any shape and size, as long as each item is `f64`, `pso_rs` represents each particle as a flat vector: `Vec<f64>`.
+//! Even though you can have particles of any shape and size, as long as each item is `f64`, `pso_rs` represents each particle as a flat slice: `Particle`, which is `[f64]`. Objective functions receive a `&Particle` borrowed from the population, and the best found minimizer is returned as a `Vec<f64>`.
 //!
 //! This means that, for example, in order to find clusters of 20 molecules in 3D space that minimize the [Lennard-Jones potential energy](https://en.wikipedia.org/wiki/Lennard-Jones_potential), you can define `dimensions` as (20, 3).
 //! If you want, you can also create a custom `reshape` function, like this one for molecule clusters below:
@@ -161,58 +364,227 @@
 //!         &pso.model.config.dimensions)
 //! );
 //! ```
+//!
+//! The `bounds` of such problems are given for the last dimension, here one pair for each of x, y and z, and are shared by all molecules. Bounds that differ between coefficients can be given as a vector with one pair per coefficient of the flattened particle instead, or computed from the multi-index of each coefficient with `Config::bounds_from_fn`:
+//!
+//! ```rust
+//! use pso_rs::*;
+//!
+//! // the first molecule is kept near the origin
+//! let config: Config = Config {
+//!     dimensions: vec![20, 3],
+//!     bounds: Config::bounds_from_fn(&[20, 3], |index| match index {
+//!         [0, _] => (-0.1, 0.1),
+//!         _ => (-2.5, 2.5),
+//!     }),
+//!     t_max: 1,
+//!     ..Config::default()
+//! };
+//! ```
 
+pub mod error;
+pub mod float;
+mod kernel;
 pub mod model;
+pub mod population;
 pub mod pso;
+mod sampling;
 
+pub use error::PsoError;
 pub use model::*;
+pub use pso::{IterationReport, RunState, Terminate};
 
-use model::Model;
 use pso::PSO;
-use std::error::Error;
+use std::cmp::Ordering;
 
 /// Creates a model and runs the PSO method
 ///
-/// # Panics
+/// The objective function can be any type implementing [`Objective`], including closures that capture their environment.
+/// The optional termination condition receives a [`RunState`] snapshot after every iteration.
 ///
-/// Panics if any particle coefficient becomes NaN (usually because of bad parameterization, e.g. c1 + c2 < 4)
-pub fn run(
-    config: Config,
-    obj_f: fn(&Particle, usize, &Vec<usize>) -> f64,
-    terminate_f: Option<fn(f64) -> bool>,
-) -> Result<PSO, Box<dyn Error>> {
-    assert_config(&config)?;
-    let mut pso = init(config, obj_f).unwrap();
-    let term_condition = match terminate_f {
-        Some(terminate_f) => terminate_f,
-        None => |_| false,
+/// # Errors
+///
+/// Returns [`PsoError::InvalidConfig`] if the configuration is invalid, [`PsoError::NaNCoefficient`] if any particle coefficient becomes NaN (usually because of bad parameterization, e.g. c1 + c2 < 4), and [`PsoError::NonFiniteObjective`] if an objective function value is not finite and the non-finite policy is [`NonFinitePolicy::Abort`]
+pub fn run<F: Float, O: Objective<F> + 'static>(
+    config: Config<F>,
+    obj_f: O,
+    terminate_f: Option<Terminate<F>>,
+) -> Result<PSO<F>, PsoError> {
+    let mut pso = init(config, obj_f)?;
+    match terminate_f {
+        Some(terminate_f) => pso.run(terminate_f)?,
+        None => pso.run(|_| false)?,
     };
-    pso.run(term_condition);
     Ok(pso)
 }
 
 /// Initializes and returns a PSO instance without running the optimization process
 ///
 /// Useful for initializing an instance for running at a later time
-pub fn init(
-    config: Config,
-    obj_f: fn(&Particle, usize, &Vec<usize>) -> f64,
-) -> Result<PSO, &'static str> {
+pub fn init<F: Float, O: Objective<F> + 'static>(
+    config: Config<F>,
+    obj_f: O,
+) -> Result<PSO<F>, PsoError> {
     assert_config(&config)?;
-    let model = Model::new(config, obj_f);
+    let model = Model::new(config, obj_f)?;
+    let pso = PSO::new(model);
+    Ok(pso)
+}
+
+/// Initializes and returns a PSO instance for an externally evaluated objective function
+///
+/// Positions to evaluate are requested with [`PSO::ask`](pso::PSO::ask) and their objective function values are fed back with [`PSO::tell`](pso::PSO::tell)
+pub fn init_ask_tell<F: Float>(config: Config<F>) -> Result<PSO<F>, PsoError> {
+    assert_config(&config)?;
+    if config.initialization == Initialization::OppositionBased {
+        return Err(PsoError::invalid_config(
+            "initialization",
+            "opposition-based initialization evaluates the initial particles, which requires an objective function",
+        ));
+    }
+    let model = Model::without_objective(config);
     let pso = PSO::new(model);
     Ok(pso)
 }
 
-fn assert_config(config: &Config) -> Result<(), &'static str> {
-    if config.c1 + config.c2 < 4.0 {
-        return Err("c1 + c2 must be greater than 4");
+fn assert_config<F: Float>(config: &Config<F>) -> Result<(), PsoError> {
+    if config.velocity_update == VelocityUpdate::Constriction
+        && config.c1 + config.c2 < F::from_f64(4.0)
+    {
+        return Err(PsoError::invalid_config(
+            "c1",
+            format!(
+                "c1 + c2 must be greater than 4, got {}",
+                config.c1 + config.c2
+            ),
+        ));
+    }
+    if config.dimensions.is_empty() {
+        return Err(PsoError::invalid_config(
+            "dimensions",
+            "dimensions must be set",
+        ));
+    }
+    if config.dimensions.contains(&0) {
+        return Err(PsoError::invalid_config(
+            "dimensions",
+            "dimensions must be greater than 0",
+        ));
+    }
+    if config.population_size == 0 {
+        return Err(PsoError::invalid_config(
+            "population_size",
+            "population_size must be greater than 0",
+        ));
+    }
+    // initial velocities are sampled within alpha times the search range, which must not be empty
+    if !(config.alpha.is_finite() && config.alpha > F::ZERO) {
+        return Err(PsoError::invalid_config(
+            "alpha",
+            format!(
+                "alpha must be finite and greater than 0, got {}",
+                config.alpha
+            ),
+        ));
+    }
+    let last_dim = config.dimensions[config.dimensions.len() - 1];
+    let flat_dim = config.dimensions.iter().product::<usize>();
+    if config.bounds.len() != last_dim && config.bounds.len() != flat_dim {
+        return Err(PsoError::invalid_config(
+            "bounds",
+            format!(
+                "bounds vector must have the length of the last dimension of the model ({}) or of the flattened particle ({}), got {}",
+                last_dim,
+                flat_dim,
+                config.bounds.len()
+            ),
+        ));
+    }
+    if let Some((i, (lower, upper))) = config
+        .bounds
+        .iter()
+        .enumerate()
+        .find(|(_, (lower, upper))| lower.partial_cmp(upper) != Some(Ordering::Less))
+    {
+        return Err(PsoError::invalid_config(
+            "bounds",
+            format!(
+                "lower bound must be less than upper bound, got ({}, {}) at index {}",
+                lower, upper, i
+            ),
+        ));
+    }
+    if config.initial_positions.len() > config.population_size {
+        return Err(PsoError::invalid_config(
+            "initial_positions",
+            format!(
+                "at most population_size ({}) initial positions can be given, got {}",
+                config.population_size,
+                config.initial_positions.len()
+            ),
+        ));
+    }
+    for (i, position) in config.initial_positions.iter().enumerate() {
+        if position.len() != flat_dim {
+            return Err(PsoError::invalid_config(
+                "initial_positions",
+                format!(
+                    "initial position {} must have a coefficient for each of the {} coefficients of the flattened particle, got {}",
+                    i,
+                    flat_dim,
+                    position.len()
+                ),
+            ));
+        }
+        if let Some(j) = position.iter().enumerate().position(|(j, &x)| {
+            let (lower, upper) = config.coefficient_bounds(j);
+            !(lower <= x && x <= upper)
+        }) {
+            return Err(PsoError::invalid_config(
+                "initial_positions",
+                format!(
+                    "coefficient {} of initial position {} must be within its bounds {:?}, got {}",
+                    j,
+                    i,
+                    config.coefficient_bounds(j),
+                    position[j]
+                ),
+            ));
+        }
     }
-    if config.dimensions.len() == 0 {
-        return Err("dimensions must be set");
+    if config.dynamic_topology == DynamicTopology::Growing
+        && !matches!(config.neighborhood_type, NeighborhoodType::Lbest)
+    {
+        return Err(PsoError::invalid_config(
+            "dynamic_topology",
+            format!(
+                "growing neighborhoods require the Lbest neighborhood type, got {}",
+                config.neighborhood_type
+            ),
+        ));
     }
-    if config.bounds.len() != config.dimensions[config.dimensions.len() - 1] {
-        return Err("bounds vector must have the same length as the last dimension of the model");
+    if let NeighborhoodType::Custom(neighborhoods) = &config.neighborhood_type {
+        if neighborhoods.len() != config.population_size {
+            return Err(PsoError::invalid_config(
+                "neighborhood_type",
+                format!(
+                    "custom topology must have a neighborhood for each of the {} particles, got {}",
+                    config.population_size,
+                    neighborhoods.len()
+                ),
+            ));
+        }
+        if let Some(i) = neighborhoods.iter().position(|neighborhood| {
+            neighborhood.is_empty() || neighborhood.iter().any(|&j| j >= config.population_size)
+        }) {
+            return Err(PsoError::invalid_config(
+                "neighborhood_type",
+                format!(
+                    "neighborhood of particle {} must be non-empty and contain only particle indices less than {}",
+                    i, config.population_size
+                ),
+            ));
+        }
     }
     Ok(())
 }
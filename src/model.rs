@@ -1,58 +1,243 @@
-use rand::{thread_rng, Rng};
+use crate::error::PsoError;
+pub use crate::float::Float;
+pub use crate::population::{Particle, Population};
+use crate::sampling;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::error::Error;
 use std::fmt;
-pub type Particle = Vec<f64>;
-pub type Population = Vec<Particle>;
+#[cfg(any(feature = "json", feature = "toml"))]
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Error returned by a failed objective function evaluation
+pub type ObjectiveError = Box<dyn Error + Send + Sync>;
+
+/// Objective function trait
+///
+/// Implemented for every `Fn(&Particle, usize, &Vec<usize>) -> f64` that is `Send + Sync`, so both plain functions and closures capturing data (measurements, lookup tables, etc.) can be used as objectives.
+/// Objectives of `f32` swarms take a `&Particle<f32>` and return an `f32` instead.
+/// Objective functions that can fail can be wrapped with [`fallible`].
+/// Implement it directly for types that need more control over how they are evaluated.
+pub trait Objective<F: Float = f64>: Send + Sync {
+    /// Computes the objective function value of a particle
+    ///
+    /// Failed evaluations are handled according to `config.objective_error_policy`
+    #[allow(clippy::ptr_arg)]
+    fn evaluate(
+        &self,
+        p: &Particle<F>,
+        flat_dim: usize,
+        dimensions: &Vec<usize>,
+    ) -> Result<F, ObjectiveError>;
+}
+
+impl<F, T> Objective<F> for T
+where
+    F: Float,
+    T: Fn(&Particle<F>, usize, &Vec<usize>) -> F + Send + Sync,
+{
+    fn evaluate(
+        &self,
+        p: &Particle<F>,
+        flat_dim: usize,
+        dimensions: &Vec<usize>,
+    ) -> Result<F, ObjectiveError> {
+        Ok(self(p, flat_dim, dimensions))
+    }
+}
+
+/// Objective function returning a `Result`, created with [`fallible`]
+pub struct Fallible<T>(T);
+
+/// Wraps an objective function returning `Result<f64, E>` so that it can be used as an [`Objective`]
+///
+/// Failed evaluations are retried, penalized or abort the run according to `config.objective_error_policy`
+pub fn fallible<F, T, E>(obj_f: T) -> Fallible<T>
+where
+    F: Float,
+    T: Fn(&Particle<F>, usize, &Vec<usize>) -> Result<F, E> + Send + Sync,
+    E: Into<ObjectiveError>,
+{
+    Fallible(obj_f)
+}
+
+impl<F, T, E> Objective<F> for Fallible<T>
+where
+    F: Float,
+    T: Fn(&Particle<F>, usize, &Vec<usize>) -> Result<F, E> + Send + Sync,
+    E: Into<ObjectiveError>,
+{
+    fn evaluate(
+        &self,
+        p: &Particle<F>,
+        flat_dim: usize,
+        dimensions: &Vec<usize>,
+    ) -> Result<F, ObjectiveError> {
+        (self.0)(p, flat_dim, dimensions).map_err(Into::into)
+    }
+}
+
+/// Maximum number of times a particle is resampled by [`NonFinitePolicy::Resample`]
+const MAX_RESAMPLES: usize = 10;
 
 /// Model struct
 ///
-/// It takes in a `Config` instance and `fn` pointer to an objective function and defines a `run` method for running Particle Swarm Optimization.
-pub struct Model {
-    pub config: Config,
+/// It takes in a `Config` instance and an objective function and defines a `run` method for running Particle Swarm Optimization.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct Model<F: Float = f64> {
+    pub config: Config<F>,
     pub flat_dim: usize,
-    pub population: Population,
-    pub population_f_scores: Vec<f64>,
-    pub x_best: Particle,
-    pub f_best: f64,
-    obj_f: fn(&Particle, usize, &Vec<usize>) -> f64,
+    pub population: Population<F>,
+    pub population_f_scores: Vec<F>,
+    pub x_best: Vec<F>,
+    pub f_best: F,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    obj_f: Option<Box<dyn Objective<F>>>,
+    evaluations: AtomicUsize,
+    pub(crate) rng: ChaCha8Rng,
 }
 
-impl Model {
+impl<F: Float> Model<F> {
     /// Creates a new Model instance
-    pub fn new(
-        config: Config,
-        obj_f: fn(p: &Particle, flat_dim: usize, dim: &Vec<usize>) -> f64,
-    ) -> Model {
+    ///
+    /// Returns an error if evaluating the initial population fails
+    pub fn new<O: Objective<F> + 'static>(
+        config: Config<F>,
+        obj_f: O,
+    ) -> Result<Model<F>, PsoError> {
+        let mut model = Model::init(config, Some(Box::new(obj_f)));
+        model.get_f_values()?;
+        if model.config.initialization == Initialization::OppositionBased {
+            model.keep_best_opposites()?;
+        }
+        Ok(model)
+    }
+
+    /// Creates a new Model instance without an objective function
+    ///
+    /// The particles are not evaluated; their objective function values are provided externally through [`PSO::tell`](crate::pso::PSO::tell)
+    pub fn without_objective(config: Config<F>) -> Model<F> {
+        Model::init(config, None)
+    }
+
+    /// Initializes the population of a new Model instance
+    fn init(config: Config<F>, obj_f: Option<Box<dyn Objective<F>>>) -> Model<F> {
         // init population
-        let mut rng = thread_rng();
+        let mut rng = match config.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
         let mut flat_dim = 1;
         for d in config.dimensions.clone() {
             flat_dim *= d;
         }
-        let mut population: Population = vec![];
+        let mut population = Population::new(config.population_size, flat_dim);
 
-        for _ in 0..config.population_size {
-            let mut particle: Particle = vec![];
-            for flat_i in 0..flat_dim {
-                let true_i = flat_i % config.dimensions[config.dimensions.len() - 1];
-                particle.push(rng.gen_range(config.bounds[true_i].0..config.bounds[true_i].1));
+        let samples = match config.initialization {
+            Initialization::Uniform | Initialization::OppositionBased => None,
+            Initialization::LatinHypercube => Some(sampling::latin_hypercube(
+                config.population_size,
+                flat_dim,
+                &mut rng,
+            )),
+            Initialization::Halton => {
+                Some(sampling::halton(config.population_size, flat_dim, &mut rng))
             }
-            population.push(particle);
+            Initialization::Sobol => {
+                Some(sampling::sobol(config.population_size, flat_dim, &mut rng))
+            }
+        };
+        match samples {
+            // scale the samples of the unit hypercube to the bounds
+            Some(samples) => {
+                for (j, (x, u)) in population
+                    .as_mut_slice()
+                    .iter_mut()
+                    .zip(samples)
+                    .enumerate()
+                {
+                    let (lower, upper) = config.coefficient_bounds(j % flat_dim);
+                    *x = lower + F::from_f64(u) * (upper - lower);
+                }
+            }
+            None => {
+                for particle in &mut population {
+                    randomize_particle(&config, particle, &mut rng);
+                }
+            }
+        }
+        // the first particles start from the given positions instead
+        for (particle, position) in population.iter_mut().zip(&config.initial_positions) {
+            particle.copy_from_slice(position);
         }
-        let population_f_scores = vec![f64::INFINITY; config.population_size];
-        let x_best = population[0].clone();
-        let f_best = population_f_scores[0].clone();
-        let mut model = Model {
+        let population_f_scores = vec![F::INFINITY; config.population_size];
+        let x_best = population[0].to_vec();
+        let f_best = population_f_scores[0];
+        Model {
             config,
             flat_dim,
             population,
             population_f_scores,
             x_best,
             f_best,
-            obj_f: obj_f,
-        };
-        model.get_f_values();
-        model
+            obj_f,
+            evaluations: AtomicUsize::new(0),
+            rng,
+        }
+    }
+
+    /// Evaluates the opposite of each sampled particle, `lower + upper - x`, and keeps the best half of the sampled particles and their opposites
+    ///
+    /// The particles starting from `config.initial_positions` are neither reflected nor replaced.
+    fn keep_best_opposites(&mut self) -> Result<(), PsoError> {
+        let first = self.config.initial_positions.len();
+        let population = self.population.clone();
+        let scores = self.population_f_scores.clone();
+        for particle in self.population.iter_mut().skip(first) {
+            for (j, x) in particle.iter_mut().enumerate() {
+                let (lower, upper) = self.config.coefficient_bounds(j);
+                *x = lower + upper - *x;
+            }
+        }
+        self.evaluate_particles(first)?;
+
+        let mut candidates: Vec<(F, &Particle<F>)> = scores[first..]
+            .iter()
+            .copied()
+            .zip(population.iter().skip(first))
+            .chain(
+                self.population_f_scores[first..]
+                    .iter()
+                    .copied()
+                    .zip(self.population.iter().skip(first)),
+            )
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut best = population.clone();
+        let mut best_scores = scores[..first].to_vec();
+        for (particle, (score, candidate)) in best.iter_mut().skip(first).zip(candidates) {
+            particle.copy_from_slice(candidate);
+            best_scores.push(score);
+        }
+        self.population = best;
+        self.population_f_scores = best_scores;
+        Ok(())
+    }
+
+    /// Sets the objective function used to evaluate particles
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_objective(&mut self, obj_f: Box<dyn Objective<F>>) {
+        self.obj_f = Some(obj_f);
+    }
+
+    /// Returns `true` if the model has an objective function to evaluate particles with
+    pub fn has_objective(&self) -> bool {
+        self.obj_f.is_some()
     }
 
     /// Computes the value of the objective function for each particle and updates best found
@@ -60,98 +245,376 @@ impl Model {
     /// Returns the objective function values for all particles
     ///
     /// Uses the rayon crate for parallel computation
-    pub fn get_f_values(&mut self) -> Vec<f64> {
+    ///
+    /// Non-finite objective function values are handled according to `config.non_finite_policy`, and particles outside the bounds are not evaluated with [`BoundaryHandling::LetFly`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PsoError::Objective`] if the evaluation of any particle fails and the objective error policy is [`ObjectiveErrorPolicy::Abort`] or its retries are exhausted,
+    /// [`PsoError::NonFiniteObjective`] if the objective function value of any particle is not finite and the non-finite policy is [`NonFinitePolicy::Abort`], and [`PsoError::InvalidUsage`] if the model was created without an objective function
+    pub fn get_f_values(&mut self) -> Result<Vec<F>, PsoError> {
+        self.evaluate_particles(0)?;
+        Ok(self.population_f_scores.to_owned())
+    }
+
+    /// Computes the value of the objective function for the particles from index `first` onwards and updates best found
+    ///
+    /// The values of the other particles are left unchanged
+    fn evaluate_particles(&mut self, first: usize) -> Result<(), PsoError> {
+        let obj_f = self.obj_f.as_deref().ok_or(PsoError::InvalidUsage(
+            "model has no objective function, use PSO::ask and PSO::tell instead",
+        ))?;
         // find the objective function value for each member of the population
-        if self.config.parallelize {
-            let iter = self.population.par_iter();
-            self.population_f_scores = iter
-                .map(|particle| {
-                    (self.obj_f)(particle, self.flat_dim, &self.config.dimensions)
-                    // self.population_f_scores[i] = f_score;
-                })
-                .collect();
+        let scores: Vec<Result<F, ObjectiveError>> = if self.config.parallelize {
+            let iter = self.population.par_iter().skip(first);
+            iter.map(|particle| self.evaluate(obj_f, particle))
+                .collect()
         } else {
-            let iter = self.population.iter();
-            self.population_f_scores = iter
-                .map(|particle| {
-                    (self.obj_f)(particle, self.flat_dim, &self.config.dimensions)
-                    // self.population_f_scores[i] = f_score;
-                })
-                .collect();
-        }
-        // update best
-        let mut f_best = self.f_best;
-        let mut x_best = self.x_best.clone();
+            let iter = self.population.iter().skip(first);
+            iter.map(|particle| self.evaluate(obj_f, particle))
+                .collect()
+        };
+        for (particle, score) in scores.into_iter().enumerate() {
+            let particle = first + particle;
+            self.population_f_scores[particle] =
+                score.map_err(|source| PsoError::Objective { particle, source })?;
+        }
+        self.handle_non_finite(true, first)?;
+        self.update_best();
+        Ok(())
+    }
+
+    /// Evaluates a particle, applying the objective error policy to failed evaluations
+    fn evaluate(
+        &self,
+        obj_f: &dyn Objective<F>,
+        particle: &Particle<F>,
+    ) -> Result<F, ObjectiveError> {
+        if self.is_flying(particle) {
+            return Ok(F::INFINITY);
+        }
+        let mut retries = 0;
+        loop {
+            self.evaluations.fetch_add(1, Ordering::Relaxed);
+            match obj_f.evaluate(particle, self.flat_dim, &self.config.dimensions) {
+                Ok(f) => return Ok(f),
+                Err(err) => match self.config.objective_error_policy {
+                    ObjectiveErrorPolicy::Retry(max_retries) if retries < max_retries => {
+                        retries += 1;
+                    }
+                    ObjectiveErrorPolicy::Penalize(penalty) => return Ok(F::from_f64(penalty)),
+                    _ => return Err(err),
+                },
+            }
+        }
+    }
+
+    /// Sets externally computed objective function values for each particle and updates best found
+    ///
+    /// Particles cannot be re-evaluated, so [`NonFinitePolicy::Resample`] treats non-finite values as `+inf`.
+    /// The values of particles outside the bounds are replaced by `+inf` with [`BoundaryHandling::LetFly`].
+    pub(crate) fn set_f_values(&mut self, scores: Vec<F>) -> Result<(), PsoError> {
+        *self.evaluations.get_mut() += scores.len();
+        self.population_f_scores = scores;
+        for particle in 0..self.population_f_scores.len() {
+            if self.is_flying(&self.population[particle]) {
+                self.population_f_scores[particle] = F::INFINITY;
+            }
+        }
+        self.handle_non_finite(false, 0)?;
+        self.update_best();
+        Ok(())
+    }
+
+    /// Returns `true` if the particle is outside the bounds and left unevaluated by [`BoundaryHandling::LetFly`]
+    fn is_flying(&self, particle: &Particle<F>) -> bool {
+        if self.config.boundary_handling != BoundaryHandling::LetFly {
+            return false;
+        }
+        particle.iter().enumerate().any(|(j, &x)| {
+            let (lower, upper) = self.config.coefficient_bounds(j);
+            x < lower || x > upper
+        })
+    }
+
+    /// Applies the non-finite policy to the objective function values of the particles from index `first` onwards
+    fn handle_non_finite(&mut self, evaluate: bool, first: usize) -> Result<(), PsoError> {
+        let obj_f = self.obj_f.as_deref().filter(|_| evaluate);
+        for particle in first..self.population_f_scores.len() {
+            let mut f = self.population_f_scores[particle];
+            if f.is_finite() || self.is_flying(&self.population[particle]) {
+                continue;
+            }
+            match self.config.non_finite_policy {
+                NonFinitePolicy::TreatAsInfinity => {}
+                NonFinitePolicy::Resample => {
+                    if let Some(obj_f) = obj_f {
+                        for _ in 0..MAX_RESAMPLES {
+                            randomize_particle(
+                                &self.config,
+                                &mut self.population[particle],
+                                &mut self.rng,
+                            );
+                            f = self
+                                .evaluate(obj_f, &self.population[particle])
+                                .map_err(|source| PsoError::Objective { particle, source })?;
+                            if f.is_finite() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                NonFinitePolicy::Abort => {
+                    return Err(PsoError::NonFiniteObjective {
+                        particle,
+                        value: f.to_f64(),
+                    });
+                }
+            }
+            self.population_f_scores[particle] = if f.is_finite() { f } else { F::INFINITY };
+        }
+        Ok(())
+    }
+
+    /// Updates the best found objective function value and minimizer from the current scores
+    fn update_best(&mut self) {
+        let mut best = None;
         for (index, &score) in self.population_f_scores.iter().enumerate() {
-            if score < f_best {
-                f_best = score;
-                x_best = self.population[index].clone();
+            if score < self.f_best {
+                self.f_best = score;
+                best = Some(index);
             }
         }
-        self.f_best = f_best;
-        self.x_best = x_best;
-        self.population_f_scores.to_owned()
+        if let Some(index) = best {
+            self.x_best.copy_from_slice(&self.population[index]);
+        }
+    }
+
+    /// Returns the number of objective function evaluations performed so far
+    ///
+    /// Counts every call to the objective function, including the evaluation of the initial population, retries of failed evaluations and resampled particles, but not the particles left unevaluated by [`BoundaryHandling::LetFly`].
+    /// Objective function values provided through [`PSO::tell`](crate::pso::PSO::tell) count as one evaluation each.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::Relaxed)
     }
 
     /// Returns the best found objective function value
-    pub fn get_f_best(&self) -> f64 {
+    pub fn get_f_best(&self) -> F {
         self.f_best
     }
 
     /// Returns the best found minimizer
-    pub fn get_x_best(&self) -> Particle {
+    pub fn get_x_best(&self) -> Vec<F> {
         self.x_best.clone()
     }
 }
 
+/// Samples the coefficients of a particle uniformly within the bounds of the problem
+fn randomize_particle<F: Float>(
+    config: &Config<F>,
+    particle: &mut Particle<F>,
+    rng: &mut ChaCha8Rng,
+) {
+    for (j, x) in particle.iter_mut().enumerate() {
+        let (lower, upper) = config.coefficient_bounds(j);
+        *x = rng.gen_range(lower..upper);
+    }
+}
+
 /// Configuration struct
 ///
 /// Used to define model parameters
+///
+/// With the `json` or `toml` features enabled, it can be loaded from configuration files. Fields missing from the file are set to their default values.
 #[derive(Debug)]
-pub struct Config {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields, bound = ""))]
+pub struct Config<F: Float = f64> {
     pub dimensions: Vec<usize>,
     pub population_size: usize,
     pub neighborhood_type: NeighborhoodType,
+    /// Radius of the ring neighborhoods of [`NeighborhoodType::Lbest`]
     pub rho: usize,
-    pub alpha: f64,
-    pub c1: f64,
-    pub c2: f64,
-    pub lr: f64,
-    pub bounds: Vec<(f64, f64)>,
+    /// How the neighborhoods change during the run
+    pub dynamic_topology: DynamicTopology,
+    /// Maximum velocity of each coefficient as a fraction of its search range, `alpha * (upper - lower)`, must be finite and greater than 0
+    ///
+    /// Initial velocities are sampled uniformly within the maximum velocity
+    pub alpha: F,
+    /// Whether velocities are clamped to the maximum velocity set by `alpha`
+    pub clamp_velocity: bool,
+    pub c1: F,
+    pub c2: F,
+    /// How the particle velocities are updated
+    pub velocity_update: VelocityUpdate,
+    pub lr: F,
+    /// Lower and upper bounds of the coefficients of the flattened particle
+    ///
+    /// Either one pair per index of the last dimension, shared by every index of the other dimensions, or one pair per coefficient, e.g. as built with [`Config::bounds_from_fn`]
+    pub bounds: Vec<(F, F)>,
+    /// How particles leaving the bounds are handled
+    pub boundary_handling: BoundaryHandling,
+    /// How the initial positions of the particles are sampled within the bounds
+    pub initialization: Initialization,
+    /// Initial positions of the first particles, e.g. known good solutions to start from
+    ///
+    /// Each position has a value within the bounds for every coefficient of the flattened particle, and at most `population_size` positions can be given.
+    /// The remaining particles are sampled according to `initialization`.
+    pub initial_positions: Vec<Vec<F>>,
     pub t_max: usize,
     pub progress_bar: bool,
     pub parallelize: bool,
+    /// How objective function values that are NaN or infinite are handled
+    pub non_finite_policy: NonFinitePolicy,
+    /// How failed objective function evaluations are handled
+    pub objective_error_policy: ObjectiveErrorPolicy,
+    /// Seed for the random number generator used for initialization and velocity updates
+    ///
+    /// Runs with the same seed and configuration are reproducible. If `None`, the generator is seeded from system entropy.
+    pub seed: Option<u64>,
 }
 
-impl Config {
-    pub fn new() -> Config {
+impl<F: Float> Config<F> {
+    pub fn new() -> Config<F> {
         Self::default()
     }
+
+    /// Returns the standard configuration of SPSO 2011
+    ///
+    /// Uses a population of 40 particles, the random topology with `k = 3` rewired on stagnation and the SPSO 2011 velocity update. `dimensions`, `bounds` and `t_max` still need to be set.
+    pub fn spso2011() -> Config<F> {
+        Config {
+            population_size: 40,
+            neighborhood_type: NeighborhoodType::RandomK { k: 3 },
+            dynamic_topology: DynamicTopology::RewireOnStagnation,
+            velocity_update: VelocityUpdate::Spso2011,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the bounds of coefficient `j` of the flattened particle
+    pub fn coefficient_bounds(&self, j: usize) -> (F, F) {
+        // bounds of the last dimension repeat every `bounds.len()` coefficients, while full-length bounds are indexed directly
+        self.bounds[j % self.bounds.len()]
+    }
+
+    /// Returns the bounds of every coefficient of a particle of shape `dimensions`, computed from the multi-index of each coefficient
+    ///
+    /// The coefficients are flattened in row-major order, so the last index changes fastest.
+    ///
+    /// ```rust
+    /// use pso_rs::*;
+    ///
+    /// // 10 atoms in 3D space, where the first atom is fixed near the origin
+    /// let config: Config = Config {
+    ///     dimensions: vec![10, 3],
+    ///     bounds: Config::bounds_from_fn(&[10, 3], |index| match index {
+    ///         [0, _] => (-0.1, 0.1),
+    ///         _ => (-2.5, 2.5),
+    ///     }),
+    ///     ..Config::default()
+    /// };
+    /// assert_eq!(config.coefficient_bounds(1), (-0.1, 0.1));
+    /// assert_eq!(config.coefficient_bounds(3), (-2.5, 2.5));
+    /// ```
+    pub fn bounds_from_fn<B>(dimensions: &[usize], mut bounds: B) -> Vec<(F, F)>
+    where
+        B: FnMut(&[usize]) -> (F, F),
+    {
+        let flat_dim = dimensions.iter().product();
+        let mut index = vec![0; dimensions.len()];
+        let mut flat_bounds = Vec::with_capacity(flat_dim);
+        for _ in 0..flat_dim {
+            flat_bounds.push(bounds(&index));
+            // increment the multi-index, carrying over to the previous dimensions
+            for k in (0..index.len()).rev() {
+                index[k] += 1;
+                if index[k] < dimensions[k] {
+                    break;
+                }
+                index[k] = 0;
+            }
+        }
+        flat_bounds
+    }
+
+    /// Parses and validates a configuration from a JSON string
+    #[cfg(feature = "json")]
+    pub fn from_json_str(s: &str) -> Result<Config<F>, PsoError> {
+        let config: Config<F> = serde_json::from_str(s)?;
+        crate::assert_config(&config)?;
+        Ok(config)
+    }
+
+    /// Reads, parses and validates a configuration from a JSON file
+    #[cfg(feature = "json")]
+    pub fn from_json_file(filepath: &str) -> Result<Config<F>, PsoError> {
+        Config::from_json_str(&fs::read_to_string(filepath)?)
+    }
+
+    /// Parses and validates a configuration from a TOML string
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Config<F>, PsoError> {
+        let config: Config<F> = toml::from_str(s)?;
+        crate::assert_config(&config)?;
+        Ok(config)
+    }
+
+    /// Reads, parses and validates a configuration from a TOML file
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(filepath: &str) -> Result<Config<F>, PsoError> {
+        Config::from_toml_str(&fs::read_to_string(filepath)?)
+    }
 }
 
-impl Default for Config {
+impl<F: Float> Default for Config<F> {
     fn default() -> Self {
         Self {
             dimensions: vec![2],
             population_size: 1000,
             neighborhood_type: NeighborhoodType::Lbest,
             rho: 2,
-            alpha: 0.1,
-            lr: 0.5,
-            c1: 2.05,
-            c2: 2.05,
-            bounds: vec![(-1.0, 1.0); 2],
+            dynamic_topology: DynamicTopology::Static,
+            alpha: F::from_f64(0.1),
+            clamp_velocity: true,
+            lr: F::from_f64(0.5),
+            c1: F::from_f64(2.05),
+            c2: F::from_f64(2.05),
+            velocity_update: VelocityUpdate::Constriction,
+            bounds: vec![(-F::ONE, F::ONE); 2],
+            boundary_handling: BoundaryHandling::Clamp,
+            initialization: Initialization::Uniform,
+            initial_positions: vec![],
             t_max: 1000,
             progress_bar: true,
             parallelize: true,
+            non_finite_policy: NonFinitePolicy::TreatAsInfinity,
+            objective_error_policy: ObjectiveErrorPolicy::Abort,
+            seed: None,
         }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NeighborhoodType {
+    /// Ring, where each particle sees itself and the `rho` particles on either side of it
     Lbest,
     Gbest,
+    /// Two-dimensional toroidal grid, where each particle sees itself and its neighbors above, below, left and right
+    ///
+    /// The grid has as many rows as the largest divisor of `population_size` that is not greater than its square root
+    VonNeumann,
+    /// The first particle is the hub, which sees every particle, while every other particle only sees itself and the hub
+    Wheel,
+    /// Each particle informs itself and `k` random particles, chosen at initialization and whenever the topology is rewired
+    RandomK {
+        k: usize,
+    },
+    /// The indices of the particles each particle sees, e.g. as built with [`NeighborhoodGraph`]
+    ///
+    /// Must contain a non-empty neighborhood for each particle of the population
+    Custom(Vec<Vec<usize>>),
 }
 
 impl fmt::Display for NeighborhoodType {
@@ -159,6 +622,258 @@ impl fmt::Display for NeighborhoodType {
         match self {
             NeighborhoodType::Lbest => write!(f, "Local neighborhood (lbest)"),
             NeighborhoodType::Gbest => write!(f, "Global neighborhood (gbest)"),
+            NeighborhoodType::VonNeumann => write!(f, "Von Neumann neighborhood"),
+            NeighborhoodType::Wheel => write!(f, "Wheel neighborhood"),
+            NeighborhoodType::RandomK { k } => write!(f, "Random neighborhood (k = {})", k),
+            NeighborhoodType::Custom(_) => write!(f, "Custom neighborhood"),
+        }
+    }
+}
+
+/// How the neighborhoods change during the run
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DynamicTopology {
+    /// The neighborhoods are created at initialization and never change
+    Static,
+    /// The topology is rewired whenever the best found value does not improve in an iteration, as in SPSO 2011
+    ///
+    /// Random topologies are drawn again, while the particles of any other topology are randomly relabeled, so that each particle gets new neighbors
+    RewireOnStagnation,
+    /// The ring radius grows linearly from `rho` to half the population over `t_max` objective function evaluations, so that the topology turns from lbest into gbest
+    ///
+    /// Requires `neighborhood_type: NeighborhoodType::Lbest`
+    Growing,
+}
+
+impl fmt::Display for DynamicTopology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicTopology::Static => write!(f, "Static topology"),
+            DynamicTopology::RewireOnStagnation => write!(f, "Rewired on stagnation"),
+            DynamicTopology::Growing => write!(f, "Growing from lbest to gbest"),
+        }
+    }
+}
+
+/// Builder for [`NeighborhoodType::Custom`] topologies
+///
+/// Every particle always sees itself; edges add the particles it is informed by.
+///
+/// ```rust
+/// use pso_rs::*;
+///
+/// // two rings of 5 particles, bridged by particles 0 and 5
+/// let mut graph = NeighborhoodGraph::new(10);
+/// for i in 0..5 {
+///     graph.connect(i, (i + 1) % 5).connect(5 + i, 5 + (i + 1) % 5);
+/// }
+/// graph.connect(0, 5);
+///
+/// let config: Config = Config {
+///     population_size: 10,
+///     neighborhood_type: graph.build(),
+///     ..Config::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct NeighborhoodGraph {
+    neighborhoods: Vec<Vec<usize>>,
+}
+
+impl NeighborhoodGraph {
+    /// Creates a graph of `population_size` particles where each particle only sees itself
+    pub fn new(population_size: usize) -> NeighborhoodGraph {
+        NeighborhoodGraph {
+            neighborhoods: (0..population_size).map(|i| vec![i]).collect(),
+        }
+    }
+
+    /// Adds an undirected edge, so that particles `a` and `b` see each other
+    pub fn connect(&mut self, a: usize, b: usize) -> &mut NeighborhoodGraph {
+        self.inform(a, b).inform(b, a)
+    }
+
+    /// Adds a directed edge, so that particle `to` sees particle `from` but not the other way around
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is not a particle of the graph
+    pub fn inform(&mut self, from: usize, to: usize) -> &mut NeighborhoodGraph {
+        let population_size = self.neighborhoods.len();
+        assert!(
+            from < population_size && to < population_size,
+            "edge from {} to {} is out of range for a graph of {} particles",
+            from,
+            to,
+            population_size
+        );
+        if !self.neighborhoods[to].contains(&from) {
+            self.neighborhoods[to].push(from);
+        }
+        self
+    }
+
+    /// Returns the topology as a [`NeighborhoodType::Custom`]
+    pub fn build(&self) -> NeighborhoodType {
+        NeighborhoodType::Custom(self.neighborhoods.clone())
+    }
+}
+
+/// Velocity update rule
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VelocityUpdate {
+    /// Clerc's constriction factor, computed from `c1 + c2`, which must be greater than 4
+    Constriction,
+    /// Shi and Eberhart's inertia weight, with random coefficients drawn from `0..1`
+    InertiaWeight(InertiaWeight),
+    /// Standard PSO 2011 (SPSO 2011), with rotation invariant hypersphere sampling and its standard parameters
+    ///
+    /// `c1`, `c2`, `alpha` and `lr` are ignored. Use [`Config::spso2011`] for the standard configuration, including its random topology.
+    Spso2011,
+}
+
+impl fmt::Display for VelocityUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VelocityUpdate::Constriction => write!(f, "Constriction factor"),
+            VelocityUpdate::InertiaWeight(w) => write!(f, "Inertia weight ({})", w),
+            VelocityUpdate::Spso2011 => write!(f, "SPSO 2011"),
+        }
+    }
+}
+
+/// Inertia weight schedule for [`VelocityUpdate::InertiaWeight`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InertiaWeight {
+    /// The same inertia weight in every iteration, e.g. `0.7298`
+    Constant(f64),
+    /// Decreases linearly from `start` to `end` over `t_max` objective function evaluations, e.g. from `0.9` to `0.4`
+    LinearDecreasing { start: f64, end: f64 },
+    /// Drawn uniformly from `0.5..1.0` for each particle in every iteration
+    Random,
+}
+
+impl fmt::Display for InertiaWeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InertiaWeight::Constant(w) => write!(f, "constant {}", w),
+            InertiaWeight::LinearDecreasing { start, end } => {
+                write!(f, "linearly decreasing from {} to {}", start, end)
+            }
+            InertiaWeight::Random => write!(f, "random"),
+        }
+    }
+}
+
+/// Strategy for sampling the initial positions of the particles
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Initialization {
+    /// Sample each coefficient uniformly within its bounds
+    Uniform,
+    /// Split the range of each coefficient into `population_size` strata and place exactly one particle in each
+    LatinHypercube,
+    /// Randomly shifted Halton sequence, which covers the search space more evenly than uniform samples in low dimensions
+    Halton,
+    /// Sobol sequence with random direction numbers, a low-discrepancy sequence that also scales to many dimensions
+    Sobol,
+    /// Sample uniformly, also evaluate the opposite `lower + upper - x` of each particle and keep the best half
+    ///
+    /// Particles starting from `initial_positions` are kept as given and take no part in the selection.
+    /// Costs one extra objective function evaluation per sampled particle, and requires an objective function, so it cannot be used with [`PSO::ask`](crate::pso::PSO::ask)
+    OppositionBased,
+}
+
+impl fmt::Display for Initialization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Initialization::Uniform => write!(f, "Uniform"),
+            Initialization::LatinHypercube => write!(f, "Latin hypercube"),
+            Initialization::Halton => write!(f, "Halton sequence"),
+            Initialization::Sobol => write!(f, "Sobol sequence"),
+            Initialization::OppositionBased => write!(f, "Opposition-based"),
+        }
+    }
+}
+
+/// Strategy for particle coefficients leaving the bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BoundaryHandling {
+    /// Set the coefficient to the bound it crossed and keep the velocity
+    ///
+    /// With [`VelocityUpdate::Spso2011`], the velocity is also reversed and halved, as in the standard
+    Clamp,
+    /// Mirror the coefficient back into the bounds at the bound it crossed and reverse the velocity
+    Reflect,
+    /// Set the coefficient to the bound it crossed and the velocity to 0
+    Absorb,
+    /// Sample the coefficient uniformly within the bounds again and keep the velocity
+    Random,
+    /// Wrap the coefficient around to the opposite bound, e.g. for angles, and keep the velocity
+    Periodic,
+    /// Let particles fly out of the bounds without evaluating them
+    ///
+    /// Particles outside the bounds get an objective function value of `+inf`, so they never become best found positions and are pulled back by their neighborhoods
+    LetFly,
+}
+
+impl fmt::Display for BoundaryHandling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundaryHandling::Clamp => write!(f, "Clamp"),
+            BoundaryHandling::Reflect => write!(f, "Reflect"),
+            BoundaryHandling::Absorb => write!(f, "Absorb"),
+            BoundaryHandling::Random => write!(f, "Random re-initialization"),
+            BoundaryHandling::Periodic => write!(f, "Periodic"),
+            BoundaryHandling::LetFly => write!(f, "Let fly"),
+        }
+    }
+}
+
+/// Policy for objective function values that are NaN or infinite
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NonFinitePolicy {
+    /// Treat the value as `+inf`, so the particle never becomes a best found position
+    TreatAsInfinity,
+    /// Resample the particle uniformly within the bounds and evaluate it again, up to 10 times, then treat the value as `+inf`
+    Resample,
+    /// Stop with [`PsoError::NonFiniteObjective`]
+    Abort,
+}
+
+impl fmt::Display for NonFinitePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonFinitePolicy::TreatAsInfinity => write!(f, "Treat as infinity"),
+            NonFinitePolicy::Resample => write!(f, "Resample particle"),
+            NonFinitePolicy::Abort => write!(f, "Abort"),
+        }
+    }
+}
+
+/// Policy for failed objective function evaluations
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ObjectiveErrorPolicy {
+    /// Stop with [`PsoError::Objective`]
+    Abort,
+    /// Use the given objective function value for the particle instead
+    Penalize(f64),
+    /// Evaluate the particle again, up to the given number of times, then stop with [`PsoError::Objective`]
+    Retry(usize),
+}
+
+impl fmt::Display for ObjectiveErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectiveErrorPolicy::Abort => write!(f, "Abort"),
+            ObjectiveErrorPolicy::Penalize(penalty) => write!(f, "Penalize with {}", penalty),
+            ObjectiveErrorPolicy::Retry(retries) => write!(f, "Retry up to {} times", retries),
         }
     }
 }
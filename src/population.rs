@@ -0,0 +1,113 @@
+use crate::float::Float;
+use rayon::prelude::*;
+use rayon::slice::{ChunksExact as ParChunksExact, ChunksExactMut as ParChunksExactMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, IndexMut};
+use std::slice::{ChunksExact, ChunksExactMut};
+
+/// Coefficients of a single particle, flattened into a slice
+pub type Particle<F = f64> = [F];
+
+/// Population of particles
+///
+/// The coefficients of all particles are stored contiguously in a single flat vector, one particle after the other.
+/// Indexing with the index of a particle returns its coefficients as a [`Particle`] slice.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Population<F = f64> {
+    data: Vec<F>,
+    flat_dim: usize,
+}
+
+impl<F: Float> Population<F> {
+    /// Creates a population of `population_size` particles of `flat_dim` coefficients, all set to `0.0`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `flat_dim` is 0
+    pub fn new(population_size: usize, flat_dim: usize) -> Population<F> {
+        assert!(flat_dim > 0, "particles must have at least one coefficient");
+        Population {
+            data: vec![F::ZERO; population_size * flat_dim],
+            flat_dim,
+        }
+    }
+
+    /// Returns the number of particles
+    pub fn len(&self) -> usize {
+        self.data.len() / self.flat_dim
+    }
+
+    /// Returns `true` if the population has no particles
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the number of coefficients of each particle
+    pub fn flat_dim(&self) -> usize {
+        self.flat_dim
+    }
+
+    /// Returns the coefficients of all particles, one particle after the other
+    pub fn as_slice(&self) -> &[F] {
+        &self.data
+    }
+
+    /// Returns the coefficients of all particles mutably, one particle after the other
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        &mut self.data
+    }
+
+    /// Iterates over the particles
+    pub fn iter(&self) -> ChunksExact<'_, F> {
+        self.data.chunks_exact(self.flat_dim)
+    }
+
+    /// Iterates mutably over the particles
+    pub fn iter_mut(&mut self) -> ChunksExactMut<'_, F> {
+        self.data.chunks_exact_mut(self.flat_dim)
+    }
+
+    /// Iterates over the particles in parallel
+    pub fn par_iter(&self) -> ParChunksExact<'_, F> {
+        self.data.par_chunks_exact(self.flat_dim)
+    }
+
+    /// Iterates mutably over the particles in parallel
+    pub fn par_iter_mut(&mut self) -> ParChunksExactMut<'_, F> {
+        self.data.par_chunks_exact_mut(self.flat_dim)
+    }
+}
+
+impl<F> Index<usize> for Population<F> {
+    type Output = Particle<F>;
+
+    fn index(&self, i: usize) -> &Particle<F> {
+        &self.data[i * self.flat_dim..(i + 1) * self.flat_dim]
+    }
+}
+
+impl<F> IndexMut<usize> for Population<F> {
+    fn index_mut(&mut self, i: usize) -> &mut Particle<F> {
+        &mut self.data[i * self.flat_dim..(i + 1) * self.flat_dim]
+    }
+}
+
+impl<'a, F: Float> IntoIterator for &'a Population<F> {
+    type Item = &'a Particle<F>;
+    type IntoIter = ChunksExact<'a, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, F: Float> IntoIterator for &'a mut Population<F> {
+    type Item = &'a mut Particle<F>;
+    type IntoIter = ChunksExactMut<'a, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -1,52 +1,135 @@
+use crate::error::PsoError;
+use crate::kernel;
 use crate::model::*;
 use indicatif::{ProgressBar, ProgressStyle};
-use rand::{thread_rng, Rng};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use std::error::Error;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::Write;
+#[cfg(feature = "serde")]
+use std::io::{BufReader, BufWriter};
+use std::time::{Duration, Instant};
+
+/// Read-only snapshot of a running optimization
+///
+/// Passed to the termination condition of [`PSO::run`] after every iteration
+#[derive(Debug)]
+pub struct RunState<'a, F: Float = f64> {
+    /// Objective function evaluations performed since the PSO instance was initialized, see [`Model::evaluations`]
+    pub evaluations: usize,
+    /// Iterations performed since the PSO instance was initialized
+    pub iteration: usize,
+    /// Time elapsed since the current call to `run`
+    pub elapsed: Duration,
+    /// Best found objective function value
+    pub f_best: F,
+    /// Best found objective function value at each iteration
+    pub best_f_trajectory: &'a [F],
+    /// Mean distance of the particles from the swarm centroid
+    pub diversity: F,
+}
+
+/// Summary of a single PSO iteration, as returned by [`PSO::step`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationReport<F: Float = f64> {
+    /// Iterations performed since the PSO instance was initialized
+    pub iteration: usize,
+    /// Objective function evaluations performed since the PSO instance was initialized, see [`Model::evaluations`]
+    pub evaluations: usize,
+    /// Best found objective function value
+    pub f_best: F,
+    /// Whether the best found objective function value improved in this iteration
+    pub improved: bool,
+    /// Mean distance of the particles from the swarm centroid
+    pub diversity: F,
+}
+
+/// Boxed termination condition, as accepted by [`run`](crate::run)
+pub type Terminate<F = f64> = Box<dyn FnMut(&RunState<F>) -> bool>;
+
+/// Inertia weight of SPSO 2011, `1 / (2 ln 2)`
+const SPSO2011_W: f64 = 0.721_347_520_444_481_7;
+/// Acceleration coefficient of SPSO 2011, `0.5 + ln 2`
+const SPSO2011_C: f64 = 1.193_147_180_559_945_3;
 
 /// PSO struct
 ///
 /// contains methods for performing Particle Swarm Optimization
-pub struct PSO {
-    chi: f64,
-    v_max: f64,
-    pub model: Model,
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct PSO<F: Float = f64> {
+    chi: F,
+    /// Maximum velocity of each coefficient of the flattened particle
+    v_max: Vec<F>,
+    pub model: Model<F>,
     neighborhoods: Vec<Vec<usize>>,
-    velocities: Population,
-    pub neigh_population: Population,
-    pub best_f_values: Vec<f64>,
-    pub best_f_trajectory: Vec<f64>,
-    pub best_x_trajectory: Vec<Particle>,
+    velocities: Population<F>,
+    pub neigh_population: Population<F>,
+    pub best_f_values: Vec<F>,
+    pub best_f_trajectory: Vec<F>,
+    pub best_x_trajectory: Vec<Vec<F>>,
+    iterations: usize,
+    evaluated: bool,
 }
 
-impl PSO {
+impl<F: Float> PSO<F> {
     /// Initialize Particle Swarm Optimization
-    pub fn new(model: Model) -> PSO {
-        let phi = model.config.c1 + model.config.c2;
-        let phi_squared = phi.powf(2.0);
-        let tmp = phi_squared - (4.0 * phi);
-        let tmp = tmp.sqrt();
-        let chi = 2.0 / (2.0 - phi - tmp).abs();
-        let v_max = model.config.alpha * 5.0;
-        let neighborhoods = PSO::create_neighborhoods(&model);
+    pub fn new(mut model: Model<F>) -> PSO<F> {
+        let chi = match model.config.velocity_update {
+            VelocityUpdate::Constriction => {
+                let phi = model.config.c1 + model.config.c2;
+                let phi_squared = phi.powi(2);
+                let tmp = phi_squared - (F::from_f64(4.0) * phi);
+                let tmp = tmp.sqrt();
+                F::from_f64(2.0) / (F::from_f64(2.0) - phi - tmp).abs()
+            }
+            VelocityUpdate::InertiaWeight(_) | VelocityUpdate::Spso2011 => F::ONE,
+        };
+        // initial velocities are sampled within alpha times the search range, even if velocities are not clamped
+        let v_init: Vec<F> = (0..model.flat_dim)
+            .map(|j| {
+                let (lower_bound, upper_bound) = model.config.coefficient_bounds(j);
+                model.config.alpha * (upper_bound - lower_bound)
+            })
+            .collect();
+        let v_max = if model.config.clamp_velocity {
+            v_init.clone()
+        } else {
+            vec![F::INFINITY; model.flat_dim]
+        };
+        let neighborhoods = Self::create_neighborhoods(&mut model);
 
         // initialize
-        let mut rng = thread_rng();
-        let mut velocities = vec![];
-        for _ in 0..model.config.population_size {
-            let mut tmp = vec![];
-            for _ in 0..model.flat_dim {
-                tmp.push(rng.gen_range(v_max * -1.0..v_max * 1.0));
+        let mut velocities = Population::new(model.config.population_size, model.flat_dim);
+        for i in 0..model.config.population_size {
+            for j in 0..model.flat_dim {
+                velocities[i][j] = match model.config.velocity_update {
+                    // SPSO 2011 samples velocities so that x + v lies within the bounds
+                    VelocityUpdate::Spso2011 => {
+                        let (lower_bound, upper_bound) = model.config.coefficient_bounds(j);
+                        let x = model.population[i][j];
+                        model.rng.gen_range(lower_bound - x..upper_bound - x)
+                    }
+                    _ => model.rng.gen_range(-v_init[j]..v_init[j]),
+                };
             }
-            velocities.push(tmp);
         }
 
         let best_f_values = model.population_f_scores.clone();
         let neigh_population = model.population.clone();
-        let best_f_trajectory = vec![model.f_best];
-        let best_x_trajectory = vec![model.x_best.clone()];
+        // models without an objective function are evaluated through `tell`
+        let evaluated = model.has_objective();
+        let (best_f_trajectory, best_x_trajectory) = if evaluated {
+            (vec![model.f_best], vec![model.x_best.clone()])
+        } else {
+            (vec![], vec![])
+        };
 
         PSO {
             chi,
@@ -58,102 +141,488 @@ impl PSO {
             neigh_population,
             best_f_trajectory,
             best_x_trajectory,
+            iterations: 0,
+            evaluated,
         }
     }
 
     /// Performs Particle Swarm Optimization
     ///
-    /// # Panics
+    /// Runs until the total number of objective function evaluations exceeds `t_max`, or until `terminate` returns `true`.
+    /// Particles left unevaluated by [`BoundaryHandling::LetFly`] do not use up evaluations, so the run also ends once the number of particle updates, `population_size` per iteration, exceeds `t_max`.
+    /// The termination condition receives a [`RunState`] snapshot after every iteration, and can capture its environment.
     ///
-    /// Panics if any particle coefficient becomes NaN
-    pub fn run(&mut self, terminate: fn(f64) -> bool) -> usize {
+    /// Returns the number of objective function evaluations performed during this call
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any iteration fails, see [`PSO::step`]
+    pub fn run<T>(&mut self, mut terminate: T) -> Result<usize, PsoError>
+    where
+        T: FnMut(&RunState<F>) -> bool,
+    {
         let mut bar: Option<ProgressBar> = None;
         if self.model.config.progress_bar {
-            bar = Some(ProgressBar::new(self.model.config.t_max as u64));
-            match bar {
-                Some(ref bar) => {
-                    bar.set_style(ProgressStyle::default_bar().template(
-                        "{msg} [{elapsed}] {bar:20.cyan/blue} {pos:>7}/{len:7} ETA: {eta}",
-                    ));
-                }
-                None => {}
-            }
+            let pbar = ProgressBar::new(self.model.config.t_max as u64);
+            pbar.set_style(
+                ProgressStyle::default_bar()
+                    .template("{msg} [{elapsed}] {bar:20.cyan/blue} {pos:>7}/{len:7} ETA: {eta}"),
+            );
+            pbar.set_position(self.model.evaluations() as u64);
+            bar = Some(pbar);
         }
-        let mut k = 0;
-        let pop_size = self.model.config.population_size;
-        loop {
-            // Update velocity and positions
-            self.update_velocity_and_pos();
-
-            // Evaluate & update best
-            self.model.get_f_values();
-            self.update_best_positions();
-
-            self.model.population = self.model.population.clone();
-            k += pop_size;
-            match bar {
-                Some(ref bar) => {
-                    bar.inc(pop_size as u64);
-                    bar.set_message(format!("{:.6}", self.model.f_best));
-                }
-                None => {}
+        let start = Instant::now();
+        let start_evaluations = self.model.evaluations();
+        let result = loop {
+            let report = match self.step() {
+                Ok(report) => report,
+                Err(err) => break Err(err),
+            };
+            if let Some(ref bar) = bar {
+                bar.set_position(report.evaluations as u64);
+                bar.set_message(format!("{:.6}", report.f_best));
             }
-            if k > self.model.config.t_max || terminate(self.model.f_best) {
-                break;
+            if self.exhausted() || terminate(&self.state(start, report)) {
+                break Ok(self.model.evaluations() - start_evaluations);
             }
+        };
+        if let Some(ref bar) = bar {
+            bar.finish_and_clear();
+        }
+        result
+    }
+
+    /// Performs a single iteration of Particle Swarm Optimization
+    ///
+    /// Updates the velocity and position of each particle, evaluates the swarm and updates the best found positions.
+    /// Unlike `run`, it does not check `t_max`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PsoError::InvalidUsage`] without changing the swarm if the model has no objective function, [`PsoError::NaNCoefficient`] if any particle coefficient becomes NaN, or any error returned by [`Model::get_f_values`]
+    pub fn step(&mut self) -> Result<IterationReport<F>, PsoError> {
+        if !self.model.has_objective() {
+            return Err(PsoError::InvalidUsage(
+                "model has no objective function, use PSO::ask and PSO::tell instead",
+            ));
         }
-        match bar {
-            Some(ref bar) => {
-                bar.finish_and_clear();
+        let f_best = self.model.f_best;
+
+        // Update velocity and positions
+        self.update_velocity_and_pos()?;
+
+        // Evaluate & update best
+        self.model.get_f_values()?;
+        self.update_best_positions();
+
+        self.iterations += 1;
+
+        Ok(IterationReport {
+            iteration: self.iterations,
+            evaluations: self.model.evaluations(),
+            f_best: self.model.f_best,
+            improved: self.model.f_best < f_best,
+            diversity: self.diversity(),
+        })
+    }
+
+    /// Returns the positions to evaluate for the next iteration
+    ///
+    /// Used together with [`PSO::tell`] when the objective function is evaluated externally.
+    /// The first call returns the initial positions of an unevaluated swarm; later calls update the velocity and position of each particle first.
+    /// Calling `ask` again before `tell` returns the same positions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PsoError::NaNCoefficient`] if any particle coefficient becomes NaN
+    pub fn ask(&mut self) -> Result<&Population<F>, PsoError> {
+        if self.evaluated {
+            self.update_velocity_and_pos()?;
+            self.evaluated = false;
+        }
+        Ok(&self.model.population)
+    }
+
+    /// Feeds back the objective function values of the positions returned by [`PSO::ask`]
+    ///
+    /// Updates the best found positions in the same way as `run`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PsoError::InvalidUsage`] if `tell` is called without `ask` or with the wrong number of scores, and [`PsoError::NonFiniteObjective`] if any score is not finite and the non-finite policy is [`NonFinitePolicy::Abort`]
+    pub fn tell(&mut self, scores: Vec<F>) -> Result<(), PsoError> {
+        if self.evaluated {
+            return Err(PsoError::InvalidUsage(
+                "positions must be requested with ask before calling tell",
+            ));
+        }
+        if scores.len() != self.model.config.population_size {
+            return Err(PsoError::InvalidUsage(
+                "scores vector must have the same length as the population",
+            ));
+        }
+        let initial = self.best_f_trajectory.is_empty();
+        self.model.set_f_values(scores)?;
+        self.update_best_positions();
+        self.evaluated = true;
+        if !initial {
+            self.iterations += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns a snapshot of the optimization state after `report`, for a run started at `start`
+    fn state(&self, start: Instant, report: IterationReport<F>) -> RunState<'_, F> {
+        RunState {
+            evaluations: report.evaluations,
+            iteration: report.iteration,
+            elapsed: start.elapsed(),
+            f_best: report.f_best,
+            best_f_trajectory: &self.best_f_trajectory,
+            diversity: report.diversity,
+        }
+    }
+
+    /// Returns the number of objective function evaluations performed since the PSO instance was initialized, see [`Model::evaluations`]
+    pub fn evaluations(&self) -> usize {
+        self.model.evaluations()
+    }
+
+    /// Returns `true` if the number of objective function evaluations or particle updates exceeds `t_max`
+    fn exhausted(&self) -> bool {
+        let t_max = self.model.config.t_max;
+        self.model.evaluations() > t_max
+            || self.iterations * self.model.config.population_size > t_max
+    }
+
+    /// Returns the number of iterations performed by `run`
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Returns the swarm diversity, as the mean Euclidean distance of the particles from the swarm centroid
+    pub fn diversity(&self) -> F {
+        let pop_size = F::from_f64(self.model.population.len() as f64);
+        let mut centroid = vec![F::ZERO; self.model.flat_dim];
+        for particle in &self.model.population {
+            for (c, &x) in centroid.iter_mut().zip(particle) {
+                *c += x / pop_size;
             }
-            None => {}
         }
-        k
+        self.model
+            .population
+            .iter()
+            .map(|particle| {
+                particle
+                    .iter()
+                    .zip(&centroid)
+                    .map(|(&x, &c)| (x - c).powi(2))
+                    .sum::<F>()
+                    .sqrt()
+            })
+            .sum::<F>()
+            / pop_size
     }
 
     /// Updates the velocity and position of each particle in the population
-    fn update_velocity_and_pos(&mut self) {
-        let mut rng = thread_rng();
-
-        for i in 0..self.model.config.population_size {
-            let lbest = &self.neigh_population[self.local_best(i)];
-            for j in 0..self.model.flat_dim {
-                let r1 = rng.gen_range(-1.0..1.0);
-                let r2 = rng.gen_range(-1.0..1.0);
-                let cog = self.model.config.c1
-                    * r1
-                    * (self.neigh_population[i][j] - self.model.population[i][j]);
-
-                let soc = self.model.config.c2 * r2 * (lbest[j] - self.model.population[i][j]);
-                let v = self.chi * (self.velocities[i][j] + cog + soc);
-
-                // check bounds
-                self.velocities[i][j] = if v.abs() > self.v_max {
-                    v.signum() * self.v_max
+    fn update_velocity_and_pos(&mut self) -> Result<(), PsoError> {
+        let r_min = match self.model.config.velocity_update {
+            VelocityUpdate::Constriction => -F::ONE,
+            VelocityUpdate::InertiaWeight(_) => F::ZERO,
+            VelocityUpdate::Spso2011 => return self.update_velocity_and_pos_spso2011(),
+        };
+        let seed = self.model.rng.gen();
+        let local_bests = self.local_bests();
+        let progress = self.model.evaluations() as f64 / self.model.config.t_max as f64;
+        let (chi, v_max) = (self.chi, &self.v_max);
+        let config = &self.model.config;
+        let neigh_population = &self.neigh_population;
+        // only the vectorized kernel reads the bounds of every coefficient
+        let (lower, upper) = if cfg!(feature = "simd") {
+            kernel::flat_bounds(config, self.model.flat_dim)
+        } else {
+            (vec![], vec![])
+        };
+        Self::update_particles(
+            &mut self.model.population,
+            &mut self.velocities,
+            config.parallelize,
+            seed,
+            |i, particle, velocity, rng, scratch| {
+                let w = Self::inertia_weight(config.velocity_update, progress, rng);
+                let lbest = &neigh_population[local_bests[i]];
+                if cfg!(feature = "simd") {
+                    let clamp = config.boundary_handling == BoundaryHandling::Clamp;
+                    kernel::update_particle(
+                        i,
+                        particle,
+                        velocity,
+                        &neigh_population[i],
+                        lbest,
+                        &lower,
+                        &upper,
+                        v_max,
+                        &kernel::Coefficients {
+                            chi,
+                            w,
+                            c1: config.c1,
+                            c2: config.c2,
+                            lr: config.lr,
+                            r_min,
+                        },
+                        clamp,
+                        rng,
+                        scratch,
+                    )?;
+                    if !clamp {
+                        for j in 0..particle.len() {
+                            let (x, v) = Self::confine(
+                                config.boundary_handling,
+                                particle[j],
+                                velocity[j],
+                                lower[j],
+                                upper[j],
+                                rng,
+                            );
+                            particle[j] = x;
+                            velocity[j] = v;
+                        }
+                    }
+                    return Ok(());
+                }
+                for (j, &lbest_j) in lbest.iter().enumerate() {
+                    let r1 = rng.gen_range(r_min..F::ONE);
+                    let r2 = rng.gen_range(r_min..F::ONE);
+                    let cog = config.c1 * r1 * (neigh_population[i][j] - particle[j]);
+
+                    let soc = config.c2 * r2 * (lbest_j - particle[j]);
+                    let v = chi * (w * velocity[j] + cog + soc);
+
+                    // check bounds
+                    velocity[j] = if v.abs() > v_max[j] {
+                        v.signum() * v_max[j]
+                    } else {
+                        v
+                    };
+
+                    let x = particle[j] + config.lr * velocity[j];
+                    if x.is_nan() {
+                        return Err(PsoError::NaNCoefficient {
+                            particle: i,
+                            dimension: j,
+                        });
+                    }
+
+                    let (lower_bound, upper_bound) = config.coefficient_bounds(j);
+                    // check bounds
+                    let (x, v) = Self::confine(
+                        config.boundary_handling,
+                        x,
+                        velocity[j],
+                        lower_bound,
+                        upper_bound,
+                        rng,
+                    );
+                    particle[j] = x;
+                    velocity[j] = v;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Updates the velocity and position of each particle in the population following SPSO 2011
+    ///
+    /// The new position is sampled at random from the hypersphere centered at the center of gravity of the particle, its best position and its neighborhood best,
+    /// which makes the update independent of the rotation of the coordinate system. Particles leaving the bounds are handled according to `config.boundary_handling`.
+    fn update_velocity_and_pos_spso2011(&mut self) -> Result<(), PsoError> {
+        let c = F::from_f64(SPSO2011_C);
+        let w = F::from_f64(SPSO2011_W);
+        let (two, three) = (F::from_f64(2.0), F::from_f64(3.0));
+        let seed = self.model.rng.gen();
+        let local_bests = self.local_bests();
+        let config = &self.model.config;
+        let neigh_population = &self.neigh_population;
+        Self::update_particles(
+            &mut self.model.population,
+            &mut self.velocities,
+            config.parallelize,
+            seed,
+            |i, particle, velocity, rng, _| {
+                let l = local_bests[i];
+                let x = &*particle;
+                let p = &neigh_population[i];
+                let lbest = &neigh_population[l];
+
+                // center of gravity of x, p and the neighborhood best
+                let center: Vec<F> = if l != i {
+                    (0..x.len())
+                        .map(|j| x[j] + c * (p[j] + lbest[j] - two * x[j]) / three)
+                        .collect()
                 } else {
-                    v
+                    (0..x.len())
+                        .map(|j| x[j] + c * (p[j] - x[j]) / two)
+                        .collect()
                 };
+                let radius = center
+                    .iter()
+                    .zip(x)
+                    .map(|(&g, &x)| (g - x).powi(2))
+                    .sum::<F>()
+                    .sqrt();
+                let x_new = Self::random_in_hypersphere(&center, radius, rng);
 
-                let x = self.model.population[i][j] + self.model.config.lr * self.velocities[i][j];
+                for (j, &x_new_j) in x_new.iter().enumerate() {
+                    let x_j = particle[j];
+                    let v = w * velocity[j] + x_new_j - x_j;
+                    let x = x_j + v;
 
-                let bound_index =
-                    j % self.model.config.dimensions[self.model.config.dimensions.len() - 1];
-                let (lower_bound, upper_bound) = self.model.config.bounds[bound_index];
-                // check bounds
-                if x > upper_bound {
-                    self.model.population[i][j] = upper_bound;
-                } else if x < lower_bound {
-                    self.model.population[i][j] = lower_bound;
-                } else {
-                    self.model.population[i][j] = x;
-                }
-                if x.is_nan() {
-                    panic!("A coefficient became NaN!");
+                    if x.is_nan() {
+                        return Err(PsoError::NaNCoefficient {
+                            particle: i,
+                            dimension: j,
+                        });
+                    }
+
+                    let (lower_bound, upper_bound) = config.coefficient_bounds(j);
+                    // confinement
+                    let (x, v) = match config.boundary_handling {
+                        BoundaryHandling::Clamp if x > upper_bound => (upper_bound, -v / two),
+                        BoundaryHandling::Clamp if x < lower_bound => (lower_bound, -v / two),
+                        boundary => Self::confine(boundary, x, v, lower_bound, upper_bound, rng),
+                    };
+                    particle[j] = x;
+                    velocity[j] = v;
                 }
+                Ok(())
+            },
+        )
+    }
+
+    /// Applies `update` to the index, position, velocity, random number generator and scratch buffers of each particle
+    ///
+    /// The scratch buffers are reused by all particles updated on the same thread.
+    /// Uses the rayon crate for parallel computation if `parallelize` is set.
+    /// Each particle draws from its own stream of a generator seeded with `seed`, so the results do not depend on the number of threads or on `parallelize`.
+    fn update_particles<U>(
+        population: &mut Population<F>,
+        velocities: &mut Population<F>,
+        parallelize: bool,
+        seed: u64,
+        update: U,
+    ) -> Result<(), PsoError>
+    where
+        U: Fn(
+                usize,
+                &mut Particle<F>,
+                &mut Particle<F>,
+                &mut ChaCha8Rng,
+                &mut kernel::Scratch<F>,
+            ) -> Result<(), PsoError>
+            + Send
+            + Sync,
+    {
+        let update =
+            |scratch: &mut kernel::Scratch<F>,
+             (i, (particle, velocity)): (usize, (&mut Particle<F>, &mut Particle<F>))| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                rng.set_stream(i as u64);
+                update(i, particle, velocity, &mut rng, scratch)
+            };
+        if parallelize {
+            population
+                .par_iter_mut()
+                .zip(velocities.par_iter_mut())
+                .enumerate()
+                .try_for_each_init(kernel::Scratch::default, update)
+        } else {
+            let mut scratch = kernel::Scratch::default();
+            population
+                .iter_mut()
+                .zip(velocities.iter_mut())
+                .enumerate()
+                .try_for_each(|item| update(&mut scratch, item))
+        }
+    }
+
+    /// Samples a point at random in a hypersphere
+    ///
+    /// As in the reference implementation of SPSO 2011, the direction is uniformly distributed and so is the distance from the center, which concentrates points near the center
+    fn random_in_hypersphere<R: Rng>(center: &[F], radius: F, rng: &mut R) -> Vec<F> {
+        // normally distributed coordinates give a uniformly distributed direction (Box-Muller transform)
+        let direction: Vec<F> = (0..center.len())
+            .map(|_| {
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                F::from_f64((-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos())
+            })
+            .collect();
+        let norm = direction.iter().map(|d| d.powi(2)).sum::<F>().sqrt();
+        let r = radius * F::from_f64(rng.gen::<f64>());
+        center
+            .iter()
+            .zip(direction)
+            .map(|(&g, d)| if norm > F::ZERO { g + r * d / norm } else { g })
+            .collect()
+    }
+
+    /// Returns the position and velocity of a coefficient after applying the boundary handling strategy
+    ///
+    /// Coefficients within `lower..=upper` are returned unchanged
+    fn confine<R: Rng>(
+        boundary_handling: BoundaryHandling,
+        x: F,
+        v: F,
+        lower: F,
+        upper: F,
+        rng: &mut R,
+    ) -> (F, F) {
+        if x >= lower && x <= upper {
+            return (x, v);
+        }
+        let clamp = |x: F| {
+            if x > upper {
+                upper
+            } else if x < lower {
+                lower
+            } else {
+                x
+            }
+        };
+        match boundary_handling {
+            BoundaryHandling::Clamp => (clamp(x), v),
+            BoundaryHandling::Absorb => (clamp(x), F::ZERO),
+            BoundaryHandling::Reflect => {
+                let x = if x > upper {
+                    upper - (x - upper)
+                } else {
+                    lower + (lower - x)
+                };
+                // steps longer than the search range are clamped after the reflection
+                (clamp(x), -v)
+            }
+            BoundaryHandling::Random => (rng.gen_range(lower..upper), v),
+            BoundaryHandling::Periodic => {
+                let t = (x - lower) / (upper - lower);
+                (clamp(lower + (t - t.floor()) * (upper - lower)), v)
             }
+            BoundaryHandling::LetFly => (x, v),
         }
     }
 
+    /// Returns the inertia weight for the next particle update, `progress` being the fraction of `t_max` evaluations performed
+    fn inertia_weight(velocity_update: VelocityUpdate, progress: f64, rng: &mut ChaCha8Rng) -> F {
+        F::from_f64(match velocity_update {
+            VelocityUpdate::Constriction => 1.0,
+            VelocityUpdate::Spso2011 => SPSO2011_W,
+            VelocityUpdate::InertiaWeight(InertiaWeight::Constant(w)) => w,
+            VelocityUpdate::InertiaWeight(InertiaWeight::LinearDecreasing { start, end }) => {
+                start + (end - start) * progress.min(1.0)
+            }
+            VelocityUpdate::InertiaWeight(InertiaWeight::Random) => rng.gen_range(0.5..1.0),
+        })
+    }
+
     /// Updates the best found positions
     fn update_best_positions(&mut self) {
         for i in 0..self.best_f_values.len() {
@@ -162,44 +631,161 @@ impl PSO {
 
             if new < old {
                 self.best_f_values[i] = new;
-                self.neigh_population[i] = self.model.population[i].clone();
+                self.neigh_population[i].copy_from_slice(&self.model.population[i]);
             }
         }
+        self.update_neighborhoods();
         self.best_f_trajectory.push(self.model.f_best);
         self.best_x_trajectory.push(self.model.x_best.clone());
     }
 
-    /// Returns the neighborhood local best
-    fn local_best(&self, i: usize) -> usize {
-        let best = PSO::argsort(&self.best_f_values);
-        for b in best {
-            if self.neighborhoods[i].iter().any(|&n| n == b) {
-                return b;
+    /// Changes the neighborhoods according to `config.dynamic_topology`
+    fn update_neighborhoods(&mut self) {
+        match self.model.config.dynamic_topology {
+            DynamicTopology::Static => {}
+            DynamicTopology::RewireOnStagnation => {
+                let stagnated = match self.best_f_trajectory.last() {
+                    Some(&f_best) => self.model.f_best >= f_best,
+                    None => false,
+                };
+                if stagnated {
+                    self.rewire();
+                }
+            }
+            DynamicTopology::Growing => {
+                let population_size = self.model.config.population_size;
+                let progress =
+                    (self.model.evaluations() as f64 / self.model.config.t_max as f64).min(1.0);
+                let rho = self.model.config.rho as f64;
+                let rho = rho + (population_size as f64 / 2.0 - rho).max(0.0) * progress;
+                let rho = rho.round() as usize;
+                Self::grow_rings(&mut self.neighborhoods, (2 * rho + 1).min(population_size));
+            }
+        }
+    }
+
+    /// Grows the ring neighborhoods in place until they contain `width` particles, never shrinking them
+    ///
+    /// Each step of the radius adds the two particles at the new ends of every ring, or only the particle opposite to each particle once the ends meet.
+    /// The rings contain the same particles as those built by [`PSO::ring`], in a different order.
+    fn grow_rings(neighborhoods: &mut [Vec<usize>], width: usize) {
+        let population_size = neighborhoods.len();
+        while neighborhoods[0].len() < width {
+            let radius = neighborhoods[0].len() / 2 + 1;
+            for (i, neighborhood) in neighborhoods.iter_mut().enumerate() {
+                let left = (i + population_size - radius) % population_size;
+                let right = (i + radius) % population_size;
+                neighborhood.push(left);
+                if right != left {
+                    neighborhood.push(right);
+                }
+            }
+        }
+    }
+
+    /// Rewires the topology, drawing random topologies again and randomly relabeling the particles of any other topology
+    ///
+    /// Relabeling a fully connected topology gives the same topology, so it is left unchanged.
+    fn rewire(&mut self) {
+        if self.fully_connected() {
+            return;
+        }
+        self.neighborhoods = match self.model.config.neighborhood_type {
+            NeighborhoodType::RandomK { .. } => Self::create_neighborhoods(&mut self.model),
+            _ => {
+                let mut labels: Vec<usize> = (0..self.model.config.population_size).collect();
+                labels.shuffle(&mut self.model.rng);
+                let mut neighborhoods = vec![vec![]; labels.len()];
+                for (i, neighborhood) in self.neighborhoods.iter().enumerate() {
+                    neighborhoods[labels[i]] = neighborhood.iter().map(|&j| labels[j]).collect();
+                }
+                neighborhoods
+            }
+        };
+    }
+
+    /// Returns the index of the particle with the best found position in the neighborhood of each particle
+    ///
+    /// Computed once per iteration, in time linear in the population size for gbest and rings, or in the total size of the neighborhoods otherwise.
+    /// Ties are broken in favor of the particle with the lowest index.
+    pub(crate) fn local_bests(&self) -> Vec<usize> {
+        let better = |a: usize, b: usize| {
+            let f = &self.best_f_values;
+            if f[b].total_cmp(&f[a]).then(b.cmp(&a)).is_lt() {
+                b
+            } else {
+                a
+            }
+        };
+        let population_size = self.best_f_values.len();
+        let ring = matches!(self.model.config.neighborhood_type, NeighborhoodType::Lbest)
+            // relabeled rings are no longer windows of consecutive particles
+            && self.model.config.dynamic_topology != DynamicTopology::RewireOnStagnation;
+        if self.fully_connected() {
+            let best = (0..population_size).reduce(better).unwrap_or(0);
+            vec![best; population_size]
+        } else if ring {
+            Self::ring_bests(&self.best_f_values, self.neighborhoods[0].len())
+        } else {
+            self.neighborhoods
+                .iter()
+                .map(|neighborhood| neighborhood.iter().copied().reduce(better).unwrap_or(0))
+                .collect()
+        }
+    }
+
+    /// Returns the index of the best value in the window of `width` consecutive values centered on each value, wrapping around
+    ///
+    /// Keeps the candidates of the sliding window in a deque of increasing values, so each index is pushed and popped at most once.
+    /// `width` must be odd and less than the number of values, as for the rings built by [`PSO::ring`].
+    fn ring_bests(best_f_values: &[F], width: usize) -> Vec<usize> {
+        let n = best_f_values.len();
+        let rho = width / 2;
+        let worse = |a: usize, b: usize| {
+            best_f_values[a]
+                .total_cmp(&best_f_values[b])
+                .then(a.cmp(&b))
+                .is_ge()
+        };
+        // positions in the sequence of windows, each paired with the index of its particle
+        let mut window: VecDeque<(usize, usize)> = VecDeque::with_capacity(width);
+        let mut bests = Vec::with_capacity(n);
+        for q in 0..n + width - 1 {
+            let j = (q + n - rho) % n;
+            while window.back().is_some_and(|&(_, back)| worse(back, j)) {
+                window.pop_back();
+            }
+            window.push_back((q, j));
+            if q + 1 >= width {
+                // the window of particle i spans positions i..i + width
+                let i = q + 1 - width;
+                while window[0].0 < i {
+                    window.pop_front();
+                }
+                bests.push(window[0].1);
+            }
+        }
+        bests
+    }
+
+    /// Returns `true` if every particle is in the neighborhood of every other particle
+    fn fully_connected(&self) -> bool {
+        match self.model.config.neighborhood_type {
+            NeighborhoodType::Gbest => true,
+            // rings covering the whole population, e.g. grown to their full size, are equivalent to gbest
+            NeighborhoodType::Lbest => {
+                self.neighborhoods[0].len() == self.model.config.population_size
             }
+            _ => false,
         }
-        0
     }
 
     /// Create the neighborhood indices for each particle
-    fn create_neighborhoods(model: &Model) -> Vec<Vec<usize>> {
+    fn create_neighborhoods(model: &mut Model<F>) -> Vec<Vec<usize>> {
         let mut neighborhoods;
         match model.config.neighborhood_type {
             NeighborhoodType::Lbest => {
-                neighborhoods = vec![];
-                for i in 0..model.config.population_size {
-                    let mut neighbor = vec![];
-                    let first_neighbor = i as i32 - model.config.rho as i32;
-                    let last_neighbor = i as i32 + model.config.rho as i32;
-
-                    for neighbor_i in first_neighbor..last_neighbor {
-                        neighbor.push(if neighbor_i < 0 {
-                            (model.config.population_size as i32 - neighbor_i) as usize
-                        } else {
-                            neighbor_i as usize
-                        });
-                    }
-                    neighborhoods.push(neighbor)
-                }
+                neighborhoods = Self::ring(model.config.population_size, model.config.rho);
             }
             NeighborhoodType::Gbest => {
                 neighborhoods = vec![];
@@ -211,19 +797,146 @@ impl PSO {
                     neighborhoods.push(tmp);
                 }
             }
+            NeighborhoodType::RandomK { k } => {
+                // each particle informs itself and k random particles
+                neighborhoods = (0..model.config.population_size).map(|i| vec![i]).collect();
+                for i in 0..model.config.population_size {
+                    for _ in 0..k {
+                        let informed = model.rng.gen_range(0..model.config.population_size);
+                        if !neighborhoods[informed].contains(&i) {
+                            neighborhoods[informed].push(i);
+                        }
+                    }
+                }
+            }
+            NeighborhoodType::VonNeumann => {
+                let n = model.config.population_size;
+                let rows = (1..=n)
+                    .take_while(|r| r * r <= n)
+                    .filter(|r| n.is_multiple_of(*r))
+                    .last()
+                    .unwrap_or(1);
+                let cols = n / rows;
+                neighborhoods = vec![];
+                for i in 0..n {
+                    let (row, col) = (i / cols, i % cols);
+                    let mut neighbor = vec![i];
+                    for (r, c) in [
+                        ((row + rows - 1) % rows, col),
+                        ((row + 1) % rows, col),
+                        (row, (col + cols - 1) % cols),
+                        (row, (col + 1) % cols),
+                    ] {
+                        let j = r * cols + c;
+                        if !neighbor.contains(&j) {
+                            neighbor.push(j);
+                        }
+                    }
+                    neighborhoods.push(neighbor);
+                }
+            }
+            NeighborhoodType::Wheel => {
+                neighborhoods = vec![(0..model.config.population_size).collect()];
+                for i in 1..model.config.population_size {
+                    neighborhoods.push(vec![0, i]);
+                }
+            }
+            NeighborhoodType::Custom(ref custom) => {
+                neighborhoods = custom.clone();
+            }
         }
         neighborhoods
     }
 
-    /// Returns the indices that would sort a vector
-    fn argsort(v: &Vec<f64>) -> Vec<usize> {
-        let mut idx = (0..v.len()).collect::<Vec<_>>();
-        idx.sort_by(|&i, &j| v[i].partial_cmp(&v[j]).expect("NaN"));
-        idx
+    /// Creates the ring neighborhoods of radius `rho`
+    ///
+    /// Each particle sees itself and the `rho` particles on either side of it, wrapping around the ends of the population
+    fn ring(population_size: usize, rho: usize) -> Vec<Vec<usize>> {
+        // the 2 * rho + 1 consecutive particles around i are distinct until they cover the whole population,
+        // so larger radii are capped instead of removing the particles they wrap around onto
+        let width = (2 * rho + 1).min(population_size);
+        (0..population_size)
+            .map(|i| {
+                (0..width)
+                    // (i - rho + offset) mod population_size, without underflow
+                    .map(|offset| {
+                        (i + population_size - rho % population_size + offset) % population_size
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the particles each particle sees
+    pub fn neighborhoods(&self) -> &[Vec<usize>] {
+        &self.neighborhoods
+    }
+
+    /// Saves the complete optimization state to a checkpoint file
+    ///
+    /// The state includes the configuration, particle positions and velocities, best found positions, trajectories and random number generator state, but not the objective function.
+    /// The file is written to a temporary path first and then renamed, so an interrupted save does not corrupt an existing checkpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pso_rs::*;
+    /// use pso_rs::pso::PSO;
+    ///
+    /// fn objective_function(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+    ///     p.iter().map(|x| x.powf(2.0)).sum()
+    /// }
+    ///
+    /// let config = Config {
+    ///     population_size: 20,
+    ///     t_max: 2000,
+    ///     progress_bar: false,
+    ///     ..Config::default()
+    /// };
+    /// # let filepath = std::env::temp_dir().join("pso_rs_doc_checkpoint.bin");
+    /// # let filepath = filepath.to_str().unwrap();
+    ///
+    /// let mut pso = pso_rs::init(config, objective_function).unwrap();
+    /// while let Some(report) = pso.next() {
+    ///     if report.unwrap().iteration % 10 == 0 {
+    ///         pso.save_checkpoint(filepath).unwrap();
+    ///     }
+    /// }
+    ///
+    /// // after an interruption, continue where the last checkpoint left off
+    /// let mut pso = PSO::load_checkpoint(filepath, objective_function).unwrap();
+    /// pso.run(|_| false).unwrap();
+    /// # std::fs::remove_file(filepath).unwrap();
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn save_checkpoint(&self, filepath: &str) -> Result<(), PsoError> {
+        let tmp_filepath = format!("{}.tmp", filepath);
+        let mut writer = BufWriter::new(File::create(&tmp_filepath)?);
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&tmp_filepath, filepath)?;
+
+        Ok(())
+    }
+
+    /// Loads the optimization state from a checkpoint file written by [`PSO::save_checkpoint`]
+    ///
+    /// Calling `run` on the returned instance continues exactly where the checkpointed run left off
+    #[cfg(feature = "serde")]
+    pub fn load_checkpoint<O: Objective<F> + 'static>(
+        filepath: &str,
+        obj_f: O,
+    ) -> Result<PSO<F>, PsoError> {
+        let reader = BufReader::new(File::open(filepath)?);
+        let mut pso: PSO<F> = bincode::deserialize_from(reader)?;
+        pso.model.set_objective(Box::new(obj_f));
+
+        Ok(pso)
     }
 
     /// Writes the best found objective function value for all iterations separated by newline characters
-    pub fn write_f_to_file(&self, filepath: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_f_to_file(&self, filepath: &str) -> Result<(), PsoError> {
         let best_f_str: Vec<String> = self
             .best_f_trajectory
             .iter()
@@ -239,13 +952,13 @@ impl PSO {
     /// Writes the best found minimizer for all iterations
     ///
     /// Vector coefficients are comma-separated, and the best vector at each iteration is terminated with a newline character
-    pub fn write_x_to_file(&self, filepath: &str) -> Result<(), Box<dyn Error>> {
+    pub fn write_x_to_file(&self, filepath: &str) -> Result<(), PsoError> {
         let best_x_str: Vec<String> = self
             .best_x_trajectory
             .iter()
             .map(|x| {
                 x.iter()
-                    .map(|coef: &f64| coef.to_string())
+                    .map(|coef: &F| coef.to_string())
                     .collect::<Vec<String>>()
                     .join(", ")
             })
@@ -257,3 +970,108 @@ impl PSO {
         Ok(())
     }
 }
+
+/// Iterates over the iterations of Particle Swarm Optimization
+///
+/// Each call to `next` performs a single [`PSO::step`], until `t_max` is exhausted as in [`PSO::run`]
+impl<F: Float> Iterator for PSO<F> {
+    type Item = Result<IterationReport<F>, PsoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted() {
+            return None;
+        }
+        Some(self.step())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_neighborhood_bests() {
+        let sphere = |p: &Particle, _: usize, _: &Vec<usize>| p.iter().map(|x| x.powi(2)).sum();
+
+        let mut graph = NeighborhoodGraph::new(6);
+        graph
+            .connect(0, 1)
+            .connect(1, 2)
+            .connect(2, 3)
+            .inform(5, 3)
+            .inform(4, 5);
+        let config = Config {
+            population_size: 6,
+            neighborhood_type: graph.build(),
+            progress_bar: false,
+            seed: Some(16),
+            ..Config::default()
+        };
+        let mut pso = crate::init(config, sphere).unwrap();
+        // particles 2 and 5 tie, and particles 0 and 1 tie with a worse value
+        pso.best_f_values = vec![3.0, 3.0, 1.0, 4.0, 2.0, 1.0];
+        // neighborhoods: 0 sees 0, 1; 1 sees 1, 0, 2; 2 sees 2, 1, 3; 3 sees 3, 2, 5; 4 sees 4; 5 sees 5, 4
+        assert_eq!(pso.local_bests(), vec![0, 2, 2, 2, 4, 5]);
+
+        // ties across the whole swarm go to the lowest index with gbest too
+        let config = Config {
+            population_size: 6,
+            neighborhood_type: NeighborhoodType::Gbest,
+            progress_bar: false,
+            seed: Some(16),
+            ..Config::default()
+        };
+        let mut pso = crate::init(config, sphere).unwrap();
+        pso.best_f_values = vec![3.0, 3.0, 1.0, 4.0, 2.0, 1.0];
+        assert_eq!(pso.local_bests(), vec![2; 6]);
+    }
+
+    #[test]
+    fn it_grows_rings_in_place() {
+        for n in 1..30 {
+            let mut neighborhoods = PSO::<f64>::ring(n, 0);
+            for width in (1..=n).step_by(2).chain([n]) {
+                PSO::<f64>::grow_rings(&mut neighborhoods, width);
+                let mut expected = PSO::<f64>::ring(n, width / 2);
+                for (neighborhood, expected) in neighborhoods.iter().zip(&mut expected) {
+                    let mut neighborhood = neighborhood.clone();
+                    neighborhood.sort_unstable();
+                    expected.sort_unstable();
+                    assert_eq!(&neighborhood, expected, "{} particles, width {}", n, width);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_ring_bests_with_a_sliding_window() {
+        let mut rng = ChaCha8Rng::seed_from_u64(16);
+        for n in 2..40 {
+            // few distinct values, so that most windows contain ties
+            let best_f_values: Vec<f64> = (0..n).map(|_| rng.gen_range(0..4) as f64).collect();
+            // rings covering the whole population use the gbest path instead
+            for rho in (0..n).take_while(|rho| 2 * rho + 1 < n) {
+                let expected: Vec<usize> = PSO::<f64>::ring(n, rho)
+                    .iter()
+                    .map(|neighborhood| {
+                        *neighborhood
+                            .iter()
+                            .min_by(|&&a, &&b| {
+                                best_f_values[a]
+                                    .total_cmp(&best_f_values[b])
+                                    .then(a.cmp(&b))
+                            })
+                            .unwrap()
+                    })
+                    .collect();
+                assert_eq!(
+                    PSO::ring_bests(&best_f_values, 2 * rho + 1),
+                    expected,
+                    "{} particles, radius {}",
+                    n,
+                    rho
+                );
+            }
+        }
+    }
+}
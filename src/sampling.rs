@@ -0,0 +1,191 @@
+//! Space-filling samples of the unit hypercube, used to initialize the population
+//!
+//! Each function returns `n` points of `d` coordinates in `0..1`, one point after the other.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Latin hypercube sample: the range of each coordinate is split into `n` strata, each containing exactly one point
+pub(crate) fn latin_hypercube<R: Rng>(n: usize, d: usize, rng: &mut R) -> Vec<f64> {
+    let mut samples = vec![0.0; n * d];
+    let mut strata: Vec<usize> = (0..n).collect();
+    for j in 0..d {
+        strata.shuffle(rng);
+        for (i, &stratum) in strata.iter().enumerate() {
+            samples[i * d + j] = (stratum as f64 + rng.gen::<f64>()) / n as f64;
+        }
+    }
+    samples
+}
+
+/// Halton sequence, with the `j`-th prime as the base of coordinate `j`
+///
+/// Each coordinate is shifted by a random offset modulo 1 (Cranley-Patterson rotation), so that different seeds give different samples
+pub(crate) fn halton<R: Rng>(n: usize, d: usize, rng: &mut R) -> Vec<f64> {
+    let mut samples = vec![0.0; n * d];
+    for (j, base) in primes(d).into_iter().enumerate() {
+        let shift: f64 = rng.gen();
+        for i in 0..n {
+            // the first point of the sequence, the origin, is skipped
+            let u = radical_inverse(i + 1, base) + shift;
+            samples[i * d + j] = u - u.floor();
+        }
+    }
+    samples
+}
+
+/// Sobol sequence, skipping the origin
+///
+/// The first coordinate is the van der Corput sequence, and every other coordinate uses the next primitive polynomial over GF(2), ordered by degree.
+/// The initial direction numbers are drawn at random, which keeps the low discrepancy of the sequence while making it depend on the seed.
+pub(crate) fn sobol<R: Rng>(n: usize, d: usize, rng: &mut R) -> Vec<f64> {
+    let mut van_der_corput = [0; 32];
+    for (k, v_k) in van_der_corput.iter_mut().enumerate() {
+        *v_k = 1 << (31 - k);
+    }
+    let mut directions = vec![van_der_corput];
+    for polynomial in primitive_polynomials(d - 1) {
+        directions.push(direction_numbers(polynomial, rng));
+    }
+
+    let mut samples = vec![0.0; n * d];
+    let mut x = vec![0u32; d];
+    for i in 0..n {
+        // Gray code order: point i + 1 differs from point i in the direction of the lowest zero bit of i
+        let c = i.trailing_ones() as usize;
+        for j in 0..d {
+            x[j] ^= directions[j][c];
+            samples[i * d + j] = x[j] as f64 / (1u64 << 32) as f64;
+        }
+    }
+    samples
+}
+
+/// Returns the direction numbers of a coordinate of the Sobol sequence, scaled to 32 bits, given its primitive polynomial
+fn direction_numbers<R: Rng>(polynomial: u64, rng: &mut R) -> [u32; 32] {
+    let s = 63 - polynomial.leading_zeros() as usize;
+    let mut v = [0u32; 32];
+    for k in 0..32 {
+        v[k] = if k < s {
+            // any odd m_k < 2^k gives a valid sequence
+            let m: u32 = rng.gen_range(0..1 << k) * 2 + 1;
+            m << (31 - k)
+        } else {
+            // v_k = a_1 v_(k-1) ^ ... ^ a_(s-1) v_(k-s+1) ^ v_(k-s) ^ (v_(k-s) >> s)
+            let mut v_k = v[k - s] ^ (v[k - s] >> s);
+            for i in 1..s {
+                if polynomial >> (s - i) & 1 == 1 {
+                    v_k ^= v[k - i];
+                }
+            }
+            v_k
+        };
+    }
+    v
+}
+
+/// Returns the first `count` primitive polynomials over GF(2), ordered by degree and then by coefficients
+///
+/// Polynomials are represented by their coefficients as bits, e.g. `0b1011` for `x^3 + x + 1`
+fn primitive_polynomials(count: usize) -> Vec<u64> {
+    let mut polynomials = Vec::with_capacity(count);
+    for s in 1..32 {
+        // a polynomial of degree s is primitive if x generates the multiplicative group modulo the polynomial, of order 2^s - 1
+        let order = (1u64 << s) - 1;
+        let factors = prime_factors(order);
+        // x reduced modulo the polynomial, which is 1 for x + 1
+        let x = if s == 1 { 1 } else { 0b10 };
+        for a in 0..1u64 << (s - 1) {
+            if polynomials.len() == count {
+                return polynomials;
+            }
+            let polynomial = 1 << s | a << 1 | 1;
+            if pow_mod(x, order, polynomial) == 1
+                && factors
+                    .iter()
+                    .all(|q| pow_mod(x, order / q, polynomial) != 1)
+            {
+                polynomials.push(polynomial);
+            }
+        }
+    }
+    polynomials
+}
+
+/// Computes `a^e` modulo `polynomial` in GF(2)[x]
+fn pow_mod(mut a: u64, mut e: u64, polynomial: u64) -> u64 {
+    let mut result = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, a, polynomial);
+        }
+        a = mul_mod(a, a, polynomial);
+        e >>= 1;
+    }
+    result
+}
+
+/// Computes `a * b` modulo `polynomial` in GF(2)[x], for `a` and `b` of lower degree than `polynomial`
+fn mul_mod(mut a: u64, mut b: u64, polynomial: u64) -> u64 {
+    let s = 63 - polynomial.leading_zeros();
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        b >>= 1;
+        a <<= 1;
+        if a >> s & 1 == 1 {
+            a ^= polynomial;
+        }
+    }
+    product
+}
+
+/// Returns the distinct prime factors of `n`
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = vec![];
+    let mut q = 2;
+    while q * q <= n {
+        if n.is_multiple_of(q) {
+            factors.push(q);
+            while n.is_multiple_of(q) {
+                n /= q;
+            }
+        }
+        q += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Returns the first `count` prime numbers
+fn primes(count: usize) -> Vec<usize> {
+    let mut primes: Vec<usize> = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| candidate % p != 0)
+        {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+/// Reflects the digits of `i` in base `base` about the radix point
+fn radical_inverse(mut i: usize, base: usize) -> f64 {
+    let mut inverse = 0.0;
+    let mut scale = 1.0 / base as f64;
+    while i > 0 {
+        inverse += (i % base) as f64 * scale;
+        i /= base;
+        scale /= base as f64;
+    }
+    inverse
+}
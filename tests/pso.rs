@@ -1,3 +1,5 @@
+#![allow(clippy::ptr_arg)]
+
 use pso_rs::*;
 
 #[test]
@@ -21,13 +23,13 @@ fn it_runs_non_parallel() {
 
     model.population[0][0] = 2.0;
     model.population[0][1] = -2.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_ne!(model.get_f_best(), 0.0);
 
     model.population[0][0] = 1.0;
     model.population[0][1] = 1.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_eq!(model.get_f_best(), 0.0);
 }
@@ -52,13 +54,13 @@ fn it_computes_correct_minimum_rosenbrock_2d() {
 
     model.population[0][0] = 2.0;
     model.population[0][1] = -2.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_ne!(model.get_f_best(), 0.0);
 
     model.population[0][0] = 1.0;
     model.population[0][1] = 1.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_eq!(model.get_f_best(), 0.0);
 }
@@ -86,14 +88,14 @@ fn it_computes_correct_minimum_rosenbrock_3d() {
     model.population[0][0] = 2.0;
     model.population[0][1] = -2.0;
     model.population[0][2] = -2.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_ne!(model.get_f_best(), 0.0);
 
     model.population[0][0] = 1.0;
     model.population[0][1] = 1.0;
     model.population[0][2] = 1.0;
-    model.get_f_values();
+    model.get_f_values().unwrap();
 
     assert_eq!(model.get_f_best(), 0.0);
 }
@@ -101,7 +103,7 @@ fn it_computes_correct_minimum_rosenbrock_3d() {
 #[test]
 fn it_computes_correct_minimum_e_lj() {
     /// Get Euclidian distance of two particles
-    fn l2(x_i: Particle, x_j: Particle, particle_dim: usize) -> f64 {
+    fn l2(x_i: &Particle, x_j: &Particle, particle_dim: usize) -> f64 {
         let mut sum: f64 = 0.0;
         for i in 0..particle_dim {
             sum += (x_i[i] - x_j[i]).powf(2.0);
@@ -110,7 +112,7 @@ fn it_computes_correct_minimum_e_lj() {
     }
 
     /// Get potential energy of two particles
-    fn v_ij(x_i: Particle, x_j: Particle, particle_dim: usize) -> f64 {
+    fn v_ij(x_i: &Particle, x_j: &Particle, particle_dim: usize) -> f64 {
         let denom: f64 = 1.0 / l2(x_i, x_j, particle_dim);
         denom.powf(12.0) - denom.powf(6.0)
     }
@@ -123,8 +125,8 @@ fn it_computes_correct_minimum_e_lj() {
                 let true_i = i * particle_dims[1];
                 let true_j = j * particle_dims[1];
                 sum += v_ij(
-                    particle[true_i..true_i + particle_dims[1]].to_vec(),
-                    particle[true_j..true_j + particle_dims[1]].to_vec(),
+                    &particle[true_i..true_i + particle_dims[1]],
+                    &particle[true_j..true_j + particle_dims[1]],
                     particle_dims[1],
                 );
             }
@@ -139,7 +141,7 @@ fn it_computes_correct_minimum_e_lj() {
         ..Config::default()
     };
 
-    let pso = pso_rs::run(config, e_lj, Some(|_| true)).unwrap();
+    let pso = pso_rs::run(config, e_lj, Some(Box::new(|_: &RunState| true))).unwrap();
 
     let mut model = pso.model;
 
@@ -155,6 +157,1221 @@ fn it_computes_correct_minimum_e_lj() {
     model.population[0][9] = 0.5193221773;
     model.population[0][10] = -0.4421382574;
     model.population[0][11] = 0.0853763087;
-    model.get_f_values();
+    model.get_f_values().unwrap();
     assert!(model.get_f_best() < -5.9999999);
 }
+
+#[test]
+fn it_accepts_closures_capturing_state() {
+    let target = vec![0.5, -0.25];
+    let objective = move |p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>| -> f64 {
+        p.iter().zip(&target).map(|(x, t)| (x - t).powf(2.0)).sum()
+    };
+
+    let config = Config {
+        t_max: 1,
+        population_size: 1,
+        progress_bar: false,
+        ..Config::default()
+    };
+    let pso = pso_rs::run(config, objective, None).unwrap();
+
+    let mut model = pso.model;
+
+    model.population[0][0] = 0.5;
+    model.population[0][1] = -0.25;
+    model.get_f_values().unwrap();
+
+    assert_eq!(model.get_f_best(), 0.0);
+}
+
+#[test]
+fn it_accepts_objective_implementors() {
+    struct Shifted {
+        offset: f64,
+    }
+
+    impl Objective for Shifted {
+        fn evaluate(
+            &self,
+            p: &Particle,
+            _flat_dim: usize,
+            _dimensions: &Vec<usize>,
+        ) -> Result<f64, ObjectiveError> {
+            Ok(p.iter().map(|x| x.powf(2.0)).sum::<f64>() + self.offset)
+        }
+    }
+
+    let config = Config {
+        t_max: 1,
+        population_size: 1,
+        progress_bar: false,
+        parallelize: false,
+        ..Config::default()
+    };
+    let pso = pso_rs::run(config, Shifted { offset: 3.0 }, None).unwrap();
+
+    let mut model = pso.model;
+
+    model.population[0][0] = 0.0;
+    model.population[0][1] = 0.0;
+    model.get_f_values().unwrap();
+
+    assert_eq!(model.get_f_best(), 3.0);
+}
+
+#[test]
+fn it_terminates_with_closure_over_run_state() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = Config {
+        population_size: 10,
+        t_max: 1_000_000,
+        progress_bar: false,
+        ..Config::default()
+    };
+    let mut pso = pso_rs::init(config, sphere).unwrap();
+
+    let mut calls = 0;
+    let evaluations = pso
+        .run(|state| {
+            calls += 1;
+            assert_eq!(state.iteration, calls);
+            assert_eq!(state.best_f_trajectory.len(), calls + 1);
+            assert!(state.diversity >= 0.0);
+            state.iteration == 3
+        })
+        .unwrap();
+
+    assert_eq!(calls, 3);
+    assert_eq!(evaluations, 30);
+    assert_eq!(pso.iterations(), 3);
+    // including the evaluation of the initial population
+    assert_eq!(pso.evaluations(), 40);
+}
+
+#[test]
+fn it_reproduces_seeded_runs() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let run = |seed, parallelize| {
+        let config = Config {
+            population_size: 20,
+            t_max: 2000,
+            progress_bar: false,
+            parallelize,
+            seed: Some(seed),
+            ..Config::default()
+        };
+        pso_rs::run(config, sphere, None).unwrap()
+    };
+
+    let first = run(42, true);
+    let second = run(42, true);
+    let sequential = run(42, false);
+    let other = run(7, true);
+
+    assert_eq!(first.best_f_trajectory, second.best_f_trajectory);
+    assert_eq!(first.best_x_trajectory, second.best_x_trajectory);
+    assert_eq!(first.best_x_trajectory, sequential.best_x_trajectory);
+    assert_ne!(first.best_x_trajectory, other.best_x_trajectory);
+
+    // particle updates draw from per-particle random streams, whichever thread they run on
+    let run = |velocity_update, parallelize| {
+        let config = Config {
+            dimensions: vec![10],
+            bounds: vec![(-5.0, 5.0); 10],
+            population_size: 200,
+            c1: 1.49445,
+            c2: 1.49445,
+            velocity_update,
+            t_max: 4000,
+            progress_bar: false,
+            parallelize,
+            seed: Some(17),
+            ..Config::default()
+        };
+        pso_rs::run(config, sphere, None).unwrap()
+    };
+    for velocity_update in [
+        VelocityUpdate::InertiaWeight(InertiaWeight::Random),
+        VelocityUpdate::Spso2011,
+    ] {
+        let parallel = run(velocity_update, true);
+        let sequential = run(velocity_update, false);
+        assert_eq!(parallel.model.population, sequential.model.population);
+        assert_eq!(parallel.best_f_trajectory, sequential.best_f_trajectory);
+    }
+}
+
+#[test]
+fn it_matches_run_with_ask_and_tell() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = || Config {
+        population_size: 10,
+        progress_bar: false,
+        seed: Some(1),
+        ..Config::default()
+    };
+
+    let mut run_pso = pso_rs::init(config(), sphere).unwrap();
+    run_pso.run(|state| state.iteration == 5).unwrap();
+
+    let mut ask_tell_pso = pso_rs::init(config(), sphere).unwrap();
+    for _ in 0..5 {
+        let scores = ask_tell_pso
+            .ask()
+            .unwrap()
+            .iter()
+            .map(|p| sphere(p, 2, &vec![2]))
+            .collect();
+        ask_tell_pso.tell(scores).unwrap();
+    }
+
+    assert_eq!(run_pso.best_f_trajectory, ask_tell_pso.best_f_trajectory);
+    assert_eq!(run_pso.best_x_trajectory, ask_tell_pso.best_x_trajectory);
+    assert_eq!(ask_tell_pso.iterations(), 5);
+    assert_eq!(run_pso.evaluations(), ask_tell_pso.evaluations());
+}
+
+#[test]
+fn it_optimizes_external_objective_with_ask_and_tell() {
+    let config = Config {
+        population_size: 10,
+        progress_bar: false,
+        ..Config::default()
+    };
+    let mut pso = pso_rs::init_ask_tell(config).unwrap();
+
+    let initial = pso.ask().unwrap().clone();
+    assert_eq!(&initial, pso.ask().unwrap());
+    assert!(pso.tell(vec![0.0; 3]).is_err());
+
+    let scores: Vec<f64> = initial.iter().map(|p| p[0].abs() + p[1].abs()).collect();
+    pso.tell(scores.clone()).unwrap();
+    assert!(pso.tell(scores).is_err());
+    assert_eq!(pso.best_f_trajectory.len(), 1);
+    assert_eq!(pso.iterations(), 0);
+
+    // stepping without an objective function fails before moving the swarm
+    assert!(matches!(pso.step(), Err(PsoError::InvalidUsage(_))));
+    assert_eq!(pso.model.population, initial);
+
+    for _ in 0..20 {
+        let scores = pso
+            .ask()
+            .unwrap()
+            .iter()
+            .map(|p| p[0].abs() + p[1].abs())
+            .collect();
+        pso.tell(scores).unwrap();
+    }
+
+    assert_eq!(pso.iterations(), 20);
+    assert_eq!(pso.best_f_trajectory.len(), 21);
+    assert!(pso.model.get_f_best() <= pso.best_f_trajectory[0]);
+}
+
+#[test]
+fn it_steps_and_iterates() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = Config {
+        population_size: 10,
+        t_max: 100,
+        progress_bar: false,
+        ..Config::default()
+    };
+    let mut pso = pso_rs::init(config, sphere).unwrap();
+
+    let report = pso.step().unwrap();
+    assert_eq!(report.iteration, 1);
+    assert_eq!(report.evaluations, 20);
+    assert_eq!(report.f_best, pso.model.get_f_best());
+
+    let reports: Vec<IterationReport> = pso.by_ref().collect::<Result<_, _>>().unwrap();
+    assert_eq!(reports.len(), 9);
+    assert_eq!(reports.last().unwrap().evaluations, 110);
+    assert!(reports.windows(2).all(|r| r[1].f_best <= r[0].f_best));
+    assert!(pso.next().is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn it_resumes_from_checkpoint() {
+    use pso_rs::pso::PSO;
+
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = Config {
+        population_size: 10,
+        progress_bar: false,
+        seed: Some(3),
+        ..Config::default()
+    };
+    let filepath = std::env::temp_dir().join("pso_rs_it_resumes_from_checkpoint.bin");
+    let filepath = filepath.to_str().unwrap();
+
+    let mut pso = pso_rs::init(config, sphere).unwrap();
+    pso.run(|state| state.iteration == 5).unwrap();
+    pso.save_checkpoint(filepath).unwrap();
+    pso.run(|state| state.iteration == 10).unwrap();
+
+    let mut resumed = PSO::load_checkpoint(filepath, sphere).unwrap();
+    std::fs::remove_file(filepath).unwrap();
+    assert_eq!(resumed.iterations(), 5);
+    resumed.run(|state| state.iteration == 10).unwrap();
+
+    assert_eq!(pso.best_f_trajectory, resumed.best_f_trajectory);
+    assert_eq!(pso.best_x_trajectory, resumed.best_x_trajectory);
+    assert_eq!(pso.model.population, resumed.model.population);
+    assert_eq!(pso.evaluations(), resumed.evaluations());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn it_loads_config_from_toml() {
+    let config: Config = Config::from_toml_str(
+        r#"
+        dimensions = [3]
+        bounds = [[-5.0, 10.0], [-5.0, 10.0], [-5.0, 10.0]]
+        population_size = 20
+        neighborhood_type = "Gbest"
+        seed = 7
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.dimensions, vec![3]);
+    assert_eq!(config.bounds, vec![(-5.0, 10.0); 3]);
+    assert_eq!(config.population_size, 20);
+    assert!(matches!(config.neighborhood_type, NeighborhoodType::Gbest));
+    assert_eq!(config.seed, Some(7));
+    assert_eq!(config.t_max, Config::<f64>::default().t_max);
+
+    let err = Config::<f64>::from_toml_str("dimensions = [3]").unwrap_err();
+    assert!(err.to_string().contains("bounds"));
+
+    let err = Config::<f64>::from_toml_str("populaton_size = 20").unwrap_err();
+    assert!(err.to_string().contains("populaton_size"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn it_loads_config_from_json() {
+    let config: Config = Config::from_json_str(
+        r#"{
+            "dimensions": [4, 3],
+            "bounds": [[-2.5, 2.5], [-2.5, 2.5], [-2.5, 2.5]],
+            "c1": 250.0,
+            "c2": 0.8
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.dimensions, vec![4, 3]);
+    assert_eq!(config.c1, 250.0);
+
+    let err = Config::<f64>::from_json_str(r#"{ "c1": 1.0 }"#).unwrap_err();
+    assert!(err.to_string().contains("c1 + c2"));
+}
+
+#[test]
+fn it_reports_invalid_config_fields() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = Config {
+        bounds: vec![(-1.0, 1.0); 3],
+        ..Config::default()
+    };
+    match pso_rs::init(config, sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "bounds"),
+        _ => panic!("expected an invalid config error"),
+    }
+
+    let config = Config {
+        bounds: vec![(1.0, -1.0); 2],
+        ..Config::default()
+    };
+    match pso_rs::init(config, sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "bounds"),
+        _ => panic!("expected an invalid config error"),
+    }
+
+    let config = Config {
+        population_size: 0,
+        ..Config::default()
+    };
+    match pso_rs::init(config, sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "population_size"),
+        _ => panic!("expected an invalid config error"),
+    }
+
+    // a zero alpha would leave no range to sample the initial velocities from, even without clamping
+    for alpha in [0.0, -0.1, f64::NAN] {
+        let config = Config {
+            alpha,
+            clamp_velocity: false,
+            ..Config::default()
+        };
+        match pso_rs::init(config, sphere) {
+            Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "alpha"),
+            _ => panic!("expected an invalid config error"),
+        }
+    }
+}
+
+#[test]
+fn it_applies_non_finite_policy() {
+    fn nan_near_origin(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        if p[0].abs() < 0.5 {
+            f64::NAN
+        } else {
+            p[0].powf(2.0)
+        }
+    }
+
+    let model_with_policy = |non_finite_policy| {
+        let config = Config {
+            population_size: 1,
+            progress_bar: false,
+            seed: Some(9),
+            ..Config::default()
+        };
+        let mut pso = pso_rs::init(config, nan_near_origin).unwrap();
+        // set after init, which would fail if the random initial particle is near the origin
+        pso.model.config.non_finite_policy = non_finite_policy;
+        pso.model.population[0][0] = 0.0;
+        pso.model
+    };
+
+    let mut model = model_with_policy(NonFinitePolicy::Abort);
+    match model.get_f_values() {
+        Err(PsoError::NonFiniteObjective { particle, value }) => {
+            assert_eq!(particle, 0);
+            assert!(value.is_nan());
+        }
+        _ => panic!("expected a non-finite objective error"),
+    }
+
+    let mut model = model_with_policy(NonFinitePolicy::TreatAsInfinity);
+    assert_eq!(model.get_f_values().unwrap(), vec![f64::INFINITY]);
+
+    let mut model = model_with_policy(NonFinitePolicy::Resample);
+    let scores = model.get_f_values().unwrap();
+    // with this seed, the particle is resampled away from the origin
+    assert!(scores[0].is_finite());
+    assert!(model.population[0][0].abs() >= 0.5);
+    assert_eq!(scores[0], model.population[0][0].powf(2.0));
+}
+
+#[test]
+fn it_handles_fallible_objectives() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn failing_outside_unit_circle(
+        p: &Particle,
+        _flat_dim: usize,
+        _dimensions: &Vec<usize>,
+    ) -> Result<f64, String> {
+        let r = p[0].powf(2.0) + p[1].powf(2.0);
+        if r > 1.0 {
+            Err(format!("no solution for r = {}", r))
+        } else {
+            Ok(r)
+        }
+    }
+
+    let config = |objective_error_policy| Config {
+        population_size: 10,
+        bounds: vec![(2.0, 3.0); 2],
+        progress_bar: false,
+        objective_error_policy,
+        ..Config::default()
+    };
+
+    match pso_rs::run(
+        config(ObjectiveErrorPolicy::Abort),
+        fallible(failing_outside_unit_circle),
+        None,
+    ) {
+        Err(PsoError::Objective { particle, source }) => {
+            assert_eq!(particle, 0);
+            assert!(source.to_string().starts_with("no solution"));
+        }
+        _ => panic!("expected an objective error"),
+    }
+
+    let pso = pso_rs::init(
+        config(ObjectiveErrorPolicy::Penalize(1e6)),
+        fallible(failing_outside_unit_circle),
+    )
+    .unwrap();
+    assert_eq!(pso.model.population_f_scores, vec![1e6; 10]);
+
+    // fails every other call, starting from the second
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let flaky = move |p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>| {
+        if counter.fetch_add(1, Ordering::SeqCst) % 2 == 1 {
+            Err("transient failure")
+        } else {
+            Ok(p[0])
+        }
+    };
+    let pso = pso_rs::init(
+        Config {
+            parallelize: false,
+            ..config(ObjectiveErrorPolicy::Retry(1))
+        },
+        fallible(flaky),
+    )
+    .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 19);
+    // retries count as evaluations
+    assert_eq!(pso.evaluations(), 19);
+    assert!(pso.model.population_f_scores.iter().all(|f| *f >= 2.0));
+}
+
+#[test]
+fn it_runs_inertia_weight_variants() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = |velocity_update| Config {
+        dimensions: vec![5],
+        bounds: vec![(-5.0, 5.0); 5],
+        population_size: 30,
+        c1: 1.49445,
+        c2: 1.49445,
+        alpha: 1.0,
+        lr: 1.0,
+        velocity_update,
+        t_max: 30_000,
+        progress_bar: false,
+        seed: Some(11),
+        ..Config::default()
+    };
+
+    match pso_rs::init(config(VelocityUpdate::Constriction), sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "c1"),
+        _ => panic!("expected an invalid config error"),
+    }
+
+    for w in [
+        InertiaWeight::Constant(0.7298),
+        InertiaWeight::LinearDecreasing {
+            start: 0.9,
+            end: 0.4,
+        },
+        InertiaWeight::Random,
+    ] {
+        let pso = pso_rs::run(config(VelocityUpdate::InertiaWeight(w)), sphere, None).unwrap();
+        assert!(
+            pso.model.get_f_best() < 1e-4,
+            "{}: {}",
+            w,
+            pso.model.get_f_best()
+        );
+    }
+}
+
+#[test]
+fn it_runs_spso2011() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    // non-separable, the coordinates are rotated with respect to the axes
+    fn schwefel_1_2(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        (0..p.len())
+            .map(|i| p[..=i].iter().sum::<f64>().powf(2.0))
+            .sum()
+    }
+
+    let config = |d| Config {
+        dimensions: vec![d],
+        bounds: vec![(-100.0, 100.0); d],
+        t_max: 40_000,
+        progress_bar: false,
+        seed: Some(2011),
+        ..Config::spso2011()
+    };
+
+    // particles are confined to the bounds
+    let mut pso = pso_rs::init(config(10), sphere).unwrap();
+    for _ in 0..50 {
+        pso.step().unwrap();
+        assert!(pso
+            .model
+            .population
+            .iter()
+            .flatten()
+            .all(|x| (-100.0..=100.0).contains(x)));
+    }
+
+    let pso = pso_rs::run(config(10), sphere, None).unwrap();
+    assert!(pso.model.get_f_best() < 1e-10, "{}", pso.model.get_f_best());
+
+    let pso = pso_rs::run(config(10), schwefel_1_2, None).unwrap();
+    assert!(pso.model.get_f_best() < 1e-6, "{}", pso.model.get_f_best());
+}
+
+#[test]
+fn it_matches_spso2011_reference_results() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    // Zambrano-Bigiarini, Clerc and Rojas, "Standard Particle Swarm Optimisation 2011 at CEC-2013", report for the sphere function
+    // in 10 dimensions, within [-100, 100] and a budget of 10000 * D evaluations, an error below the 1e-8 threshold in every run
+    let d = 10;
+    let runs = 5;
+    let errors: Vec<f64> = (0..runs)
+        .map(|seed| {
+            let config = Config {
+                dimensions: vec![d],
+                bounds: vec![(-100.0, 100.0); d],
+                t_max: 10_000 * d,
+                progress_bar: false,
+                seed: Some(seed),
+                ..Config::spso2011()
+            };
+            pso_rs::run(config, sphere, None)
+                .unwrap()
+                .model
+                .get_f_best()
+        })
+        .collect();
+    let successes = errors.iter().filter(|&&error| error < 1e-8).count();
+    assert_eq!(successes, runs as usize, "{:?}", errors);
+    let mean_error = errors.iter().sum::<f64>() / runs as f64;
+    assert!(mean_error < 1e-8, "{}", mean_error);
+}
+
+#[test]
+fn it_builds_topologies() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = |neighborhood_type| Config {
+        dimensions: vec![2],
+        bounds: vec![(-5.0, 5.0); 2],
+        population_size: 12,
+        neighborhood_type,
+        t_max: 12_000,
+        progress_bar: false,
+        seed: Some(13),
+        ..Config::default()
+    };
+
+    // 3 x 4 toroidal grid
+    let pso = pso_rs::init(config(NeighborhoodType::VonNeumann), sphere).unwrap();
+    let mut neighborhood = pso.neighborhoods()[5].clone();
+    neighborhood.sort_unstable();
+    assert_eq!(neighborhood, vec![1, 4, 5, 6, 9]);
+    let mut neighborhood = pso.neighborhoods()[0].clone();
+    neighborhood.sort_unstable();
+    assert_eq!(neighborhood, vec![0, 1, 3, 4, 8]);
+
+    let pso = pso_rs::init(config(NeighborhoodType::Wheel), sphere).unwrap();
+    assert_eq!(pso.neighborhoods()[0], (0..12).collect::<Vec<_>>());
+    assert_eq!(pso.neighborhoods()[7], vec![0, 7]);
+
+    let pso = pso_rs::init(config(NeighborhoodType::RandomK { k: 3 }), sphere).unwrap();
+    assert!(pso
+        .neighborhoods()
+        .iter()
+        .enumerate()
+        .all(|(i, neighborhood)| neighborhood.contains(&i)));
+
+    let mut graph = NeighborhoodGraph::new(12);
+    for i in 0..12 {
+        graph.connect(i, (i + 1) % 12);
+    }
+    graph.inform(0, 6);
+    let pso = pso_rs::init(config(graph.build()), sphere).unwrap();
+    assert_eq!(pso.neighborhoods()[6], vec![6, 5, 7, 0]);
+    assert_eq!(pso.neighborhoods()[0], vec![0, 1, 11]);
+
+    for neighborhood_type in [
+        NeighborhoodType::VonNeumann,
+        NeighborhoodType::Wheel,
+        NeighborhoodType::RandomK { k: 3 },
+        graph.build(),
+    ] {
+        let name = neighborhood_type.to_string();
+        let pso = pso_rs::run(config(neighborhood_type), sphere, None).unwrap();
+        assert!(
+            pso.model.get_f_best() < 1e-3,
+            "{}: {}",
+            name,
+            pso.model.get_f_best()
+        );
+    }
+
+    let invalid =
+        NeighborhoodType::Custom(vec![vec![0]; 12].into_iter().chain([vec![12]]).collect());
+    match pso_rs::init(config(invalid), sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "neighborhood_type"),
+        _ => panic!("expected an invalid config error"),
+    }
+    let mut invalid = vec![vec![0]; 12];
+    invalid[3] = vec![12];
+    match pso_rs::init(config(NeighborhoodType::Custom(invalid)), sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "neighborhood_type"),
+        _ => panic!("expected an invalid config error"),
+    }
+}
+
+#[test]
+fn it_changes_topologies_during_the_run() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    fn flat(_p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        0.0
+    }
+
+    let config = |neighborhood_type, dynamic_topology| Config {
+        dimensions: vec![2],
+        bounds: vec![(-5.0, 5.0); 2],
+        population_size: 20,
+        neighborhood_type,
+        dynamic_topology,
+        t_max: 8000,
+        progress_bar: false,
+        seed: Some(14),
+        ..Config::default()
+    };
+
+    // the best found value never improves, so the grid is relabeled in every iteration
+    let mut pso = pso_rs::init(
+        config(
+            NeighborhoodType::VonNeumann,
+            DynamicTopology::RewireOnStagnation,
+        ),
+        flat,
+    )
+    .unwrap();
+    let initial = pso.neighborhoods().to_vec();
+    pso.step().unwrap();
+    assert_ne!(pso.neighborhoods(), initial.as_slice());
+    for (i, neighborhood) in pso.neighborhoods().iter().enumerate() {
+        assert!(neighborhood.contains(&i));
+        assert_eq!(neighborhood.len(), 5);
+    }
+
+    // relabeling would give the same fully connected topology, so it is left as is
+    let mut pso = pso_rs::init(
+        config(NeighborhoodType::Gbest, DynamicTopology::RewireOnStagnation),
+        flat,
+    )
+    .unwrap();
+    let initial = pso.neighborhoods().to_vec();
+    pso.step().unwrap();
+    assert_eq!(pso.neighborhoods(), initial.as_slice());
+
+    let mut pso = pso_rs::init(
+        config(NeighborhoodType::Lbest, DynamicTopology::Growing),
+        sphere,
+    )
+    .unwrap();
+    let initial_size = pso.neighborhoods()[0].len();
+    pso.run(|_| false).unwrap();
+    assert!(initial_size < 20);
+    assert!(pso
+        .neighborhoods()
+        .iter()
+        .all(|neighborhood| neighborhood.len() == 20));
+    assert!(pso.model.get_f_best() < 1e-3, "{}", pso.model.get_f_best());
+
+    match pso_rs::init(
+        config(NeighborhoodType::Gbest, DynamicTopology::Growing),
+        sphere,
+    ) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "dynamic_topology"),
+        _ => panic!("expected an invalid config error"),
+    }
+}
+
+#[test]
+fn it_builds_symmetric_wrapping_rings() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = |population_size, rho| Config {
+        dimensions: vec![2],
+        bounds: vec![(-5.0, 5.0); 2],
+        population_size,
+        neighborhood_type: NeighborhoodType::Lbest,
+        rho,
+        progress_bar: false,
+        seed: Some(15),
+        ..Config::default()
+    };
+    let sorted = |neighborhood: &Vec<usize>| {
+        let mut neighborhood = neighborhood.clone();
+        neighborhood.sort_unstable();
+        neighborhood
+    };
+
+    let pso = pso_rs::init(config(10, 2), sphere).unwrap();
+    let neighborhoods = pso.neighborhoods();
+    assert_eq!(sorted(&neighborhoods[5]), vec![3, 4, 5, 6, 7]);
+    assert_eq!(sorted(&neighborhoods[0]), vec![0, 1, 2, 8, 9]);
+    assert_eq!(sorted(&neighborhoods[1]), vec![0, 1, 2, 3, 9]);
+    assert_eq!(sorted(&neighborhoods[9]), vec![0, 1, 7, 8, 9]);
+    // the ring is symmetric
+    for (i, neighborhood) in neighborhoods.iter().enumerate() {
+        for &j in neighborhood {
+            assert!(neighborhoods[j].contains(&i), "{} sees {}", i, j);
+        }
+    }
+
+    let pso = pso_rs::init(config(10, 1), sphere).unwrap();
+    assert_eq!(sorted(&pso.neighborhoods()[0]), vec![0, 1, 9]);
+
+    // radii covering the whole population do not repeat particles
+    let pso = pso_rs::init(config(4, 3), sphere).unwrap();
+    for neighborhood in pso.neighborhoods() {
+        assert_eq!(sorted(neighborhood), vec![0, 1, 2, 3]);
+    }
+    for (population_size, rho) in [(5, 2), (6, 3), (7, 5)] {
+        let pso = pso_rs::init(config(population_size, rho), sphere).unwrap();
+        for neighborhood in pso.neighborhoods() {
+            assert_eq!(
+                sorted(neighborhood),
+                (0..population_size).collect::<Vec<_>>()
+            );
+        }
+    }
+    let pso = pso_rs::init(config(6, 2), sphere).unwrap();
+    assert_eq!(sorted(&pso.neighborhoods()[0]), vec![0, 1, 2, 4, 5]);
+}
+
+#[test]
+fn it_runs_f32_swarms() {
+    fn sphere(p: &Particle<f32>, _flat_dim: usize, _dimensions: &Vec<usize>) -> f32 {
+        p.iter().map(|x| x.powi(2)).sum()
+    }
+
+    let config = |velocity_update| Config::<f32> {
+        dimensions: vec![5],
+        bounds: vec![(-5.0, 5.0); 5],
+        population_size: 40,
+        c1: 1.49445,
+        c2: 1.49445,
+        alpha: 1.0,
+        lr: 1.0,
+        velocity_update,
+        t_max: 20_000,
+        progress_bar: false,
+        seed: Some(19),
+        ..Config::default()
+    };
+
+    for velocity_update in [
+        VelocityUpdate::InertiaWeight(InertiaWeight::Constant(0.7298)),
+        VelocityUpdate::Spso2011,
+    ] {
+        let pso = pso_rs::run(config(velocity_update), sphere, None).unwrap();
+        let f_best: f32 = pso.model.get_f_best();
+        assert!(f_best < 1e-4, "{}: {}", velocity_update, f_best);
+        assert_eq!(pso.model.population.as_slice().len(), 40 * 5);
+    }
+
+    let mut pso = pso_rs::init_ask_tell(config(VelocityUpdate::Spso2011)).unwrap();
+    for _ in 0..100 {
+        let scores = pso
+            .ask()
+            .unwrap()
+            .iter()
+            .map(|p| sphere(p, 5, &vec![5]))
+            .collect();
+        pso.tell(scores).unwrap();
+    }
+    assert!(pso.model.get_f_best() < 1e-2);
+}
+
+#[test]
+fn it_handles_boundaries() {
+    // the minimum lies outside the bounds, so the best feasible position is on the upper bound
+    fn shifted_sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| (x - 2.0).powf(2.0)).sum()
+    }
+
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| (x - 0.5).powf(2.0)).sum()
+    }
+
+    for boundary_handling in [
+        BoundaryHandling::Clamp,
+        BoundaryHandling::Reflect,
+        BoundaryHandling::Absorb,
+        BoundaryHandling::Random,
+        BoundaryHandling::Periodic,
+        BoundaryHandling::LetFly,
+    ] {
+        let config = || Config {
+            dimensions: vec![5],
+            bounds: vec![(-1.0, 1.0); 5],
+            population_size: 40,
+            neighborhood_type: NeighborhoodType::Gbest,
+            boundary_handling,
+            t_max: 20_000,
+            progress_bar: false,
+            seed: Some(21),
+            ..Config::default()
+        };
+
+        let mut pso = pso_rs::init(config(), shifted_sphere).unwrap();
+        let mut left_bounds = false;
+        for _ in 0..500 {
+            pso.step().unwrap();
+            left_bounds |= !pso
+                .model
+                .population
+                .iter()
+                .flatten()
+                .all(|x| (-1.0..=1.0).contains(x));
+        }
+        // only particles that are let fly leave the bounds, and they never become best found positions
+        assert_eq!(
+            left_bounds,
+            boundary_handling == BoundaryHandling::LetFly,
+            "{}",
+            boundary_handling
+        );
+        // particles outside the bounds are not evaluated
+        assert_eq!(
+            pso.evaluations() < 40 * 501,
+            boundary_handling == BoundaryHandling::LetFly,
+            "{}",
+            boundary_handling
+        );
+        let x_best = pso.model.get_x_best();
+        assert!(x_best.iter().all(|x| (-1.0..=1.0).contains(x)));
+        // clamped particles stay on the bounds they hit
+        if let BoundaryHandling::Clamp | BoundaryHandling::Absorb = boundary_handling {
+            assert_eq!(pso.model.get_f_best(), 5.0, "{}", boundary_handling);
+        }
+
+        let pso = pso_rs::run(config(), sphere, None).unwrap();
+        let f_best = pso.model.get_f_best();
+        assert!(f_best < 1e-1, "{}: {}", boundary_handling, f_best);
+    }
+}
+
+#[test]
+fn it_sets_bounds_per_coefficient() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    // row i of a 4 x 2 particle is bounded to (i, i + 1), and the second column is negated
+    let bounds = Config::bounds_from_fn(&[4, 2], |index| {
+        let i = index[0] as f64;
+        match index[1] {
+            0 => (i, i + 1.0),
+            _ => (-i - 1.0, -i),
+        }
+    });
+    assert_eq!(bounds.len(), 8);
+    assert_eq!(bounds[3], (-2.0, -1.0));
+    assert_eq!(bounds[6], (3.0, 4.0));
+
+    let config = Config {
+        dimensions: vec![4, 2],
+        bounds: bounds.clone(),
+        population_size: 20,
+        alpha: 0.25,
+        t_max: 4000,
+        progress_bar: false,
+        seed: Some(22),
+        ..Config::default()
+    };
+    let pso = pso_rs::run(config, sphere, None).unwrap();
+    for particle in &pso.model.population {
+        for (x, (lower, upper)) in particle.iter().zip(&bounds) {
+            assert!(lower <= x && x <= upper);
+        }
+    }
+    // the minimum is at the bound of each coefficient closest to the origin
+    let x_best = pso.model.get_x_best();
+    for (x, (lower, upper)) in x_best.iter().zip(&bounds) {
+        let closest = if lower.abs() < upper.abs() {
+            lower
+        } else {
+            upper
+        };
+        assert!((x - closest).abs() < 1e-6, "{:?}", x_best);
+    }
+
+    // the broadcasting form is still accepted, but not bounds of any other length
+    let config = Config {
+        dimensions: vec![4, 2],
+        bounds: vec![(-1.0, 1.0); 2],
+        progress_bar: false,
+        ..Config::default()
+    };
+    assert!(pso_rs::init(config, sphere).is_ok());
+    let config = Config {
+        dimensions: vec![4, 2],
+        bounds: vec![(-1.0, 1.0); 4],
+        ..Config::default()
+    };
+    match pso_rs::init(config, sphere) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "bounds"),
+        _ => panic!("expected an invalid config error"),
+    }
+}
+
+#[test]
+fn it_clamps_velocities_per_coefficient() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let config = |clamp_velocity| Config {
+        dimensions: vec![2],
+        bounds: vec![(0.0, 1.0), (0.0, 1e4)],
+        population_size: 20,
+        alpha: 0.1,
+        clamp_velocity,
+        lr: 1.0,
+        progress_bar: false,
+        seed: Some(23),
+        ..Config::default()
+    };
+
+    // the maximum velocity, and so the largest step, is proportional to the search range of each coefficient
+    let v_max = [0.1, 1e3];
+    for clamp_velocity in [true, false] {
+        let mut largest_step = [0.0_f64; 2];
+        let mut pso = pso_rs::init(config(clamp_velocity), sphere).unwrap();
+        for _ in 0..50 {
+            let previous = pso.model.population.clone();
+            pso.step().unwrap();
+            for (particle, previous) in pso.model.population.iter().zip(&previous) {
+                for j in 0..2 {
+                    largest_step[j] = largest_step[j].max((particle[j] - previous[j]).abs());
+                }
+            }
+        }
+        for j in 0..2 {
+            assert_eq!(
+                largest_step[j] <= v_max[j] + 1e-9,
+                clamp_velocity,
+                "{:?}",
+                largest_step
+            );
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+#[test]
+fn it_clamps_and_reports_nan_in_vectorized_kernel() {
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| x.powf(2.0)).sum()
+    }
+
+    let bounds = [(-1.0, 1.0), (-10.0, 10.0), (0.0, 100.0)];
+    let config = || Config {
+        dimensions: vec![3, 3],
+        bounds: bounds.to_vec(),
+        population_size: 20,
+        alpha: 0.5,
+        progress_bar: false,
+        seed: Some(20),
+        ..Config::default()
+    };
+    let v_max = [1.0, 10.0, 50.0];
+
+    let mut pso = pso_rs::init(config(), sphere).unwrap();
+    for _ in 0..50 {
+        let previous = pso.model.population.clone();
+        pso.step().unwrap();
+        for (particle, previous) in pso.model.population.iter().zip(&previous) {
+            for (j, (x, previous)) in particle.iter().zip(previous).enumerate() {
+                let (lower, upper) = bounds[j % 3];
+                assert!(lower <= *x && *x <= upper, "{:?}", particle);
+                assert!((x - previous).abs() <= v_max[j % 3] + 1e-9);
+            }
+        }
+    }
+
+    let mut pso = pso_rs::init(config(), sphere).unwrap();
+    pso.model.population[3][4] = f64::NAN;
+    match pso.step() {
+        Err(PsoError::NaNCoefficient {
+            particle: 3,
+            dimension: 4,
+        }) => {}
+        other => panic!(
+            "expected a NaN coefficient error, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+fn it_initializes_with_sampling_strategies() {
+    // shifted away from the center of the bounds, where the first point of the Sobol sequence lies
+    fn sphere(p: &Particle, _flat_dim: usize, _dimensions: &Vec<usize>) -> f64 {
+        p.iter().map(|x| (x - 0.3).powf(2.0)).sum()
+    }
+
+    let config = |initialization| Config {
+        dimensions: vec![8],
+        bounds: vec![(-1.0, 1.0); 8],
+        population_size: 16,
+        initialization,
+        c1: 1.49445,
+        c2: 1.49445,
+        lr: 1.0,
+        velocity_update: VelocityUpdate::InertiaWeight(InertiaWeight::Constant(0.7298)),
+        t_max: 4000,
+        progress_bar: false,
+        seed: Some(24),
+        ..Config::default()
+    };
+
+    for initialization in [
+        Initialization::Uniform,
+        Initialization::LatinHypercube,
+        Initialization::Halton,
+        Initialization::Sobol,
+        Initialization::OppositionBased,
+    ] {
+        let pso = pso_rs::init(config(initialization), sphere).unwrap();
+        assert!(pso
+            .model
+            .population
+            .iter()
+            .flatten()
+            .all(|x| (-1.0..=1.0).contains(x)));
+        let pso = pso_rs::run(config(initialization), sphere, None).unwrap();
+        assert!(pso.model.get_f_best() < 1e-6, "{}", initialization);
+    }
+
+    // each of the 16 strata of each coefficient contains exactly one particle
+    let pso = pso_rs::init(config(Initialization::LatinHypercube), sphere).unwrap();
+    for j in 0..8 {
+        let mut strata: Vec<usize> = pso
+            .model
+            .population
+            .iter()
+            .map(|particle| ((particle[j] + 1.0) * 8.0) as usize)
+            .collect();
+        strata.sort_unstable();
+        assert_eq!(strata, (0..16).collect::<Vec<_>>());
+    }
+
+    // the first two coefficients of the Sobol sequence do not depend on the seed
+    let pso = pso_rs::init(config(Initialization::Sobol), sphere).unwrap();
+    let population = &pso.model.population;
+    assert_eq!(population[0][..2], [0.0, 0.0]);
+    assert_eq!(population[1][..2], [0.5, -0.5]);
+    assert_eq!(population[2][..2], [-0.5, 0.5]);
+
+    // the opposites of the uniform particles only replace them if they are better
+    let uniform = pso_rs::init(config(Initialization::Uniform), sphere).unwrap();
+    let opposition = pso_rs::init(config(Initialization::OppositionBased), sphere).unwrap();
+    let sum = |scores: &Vec<f64>| scores.iter().sum::<f64>();
+    assert!(sum(&opposition.model.population_f_scores) < sum(&uniform.model.population_f_scores));
+    assert!(opposition.model.get_f_best() <= uniform.model.get_f_best());
+
+    match pso_rs::init_ask_tell(config(Initialization::OppositionBased)) {
+        Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "initialization"),
+        _ => panic!("expected an invalid config error"),
+    }
+}
+
+#[test]
+fn it_starts_from_initial_positions() {
+    fn rosenbrock(p: &Particle, _flat_dim: usize, dimensions: &Vec<usize>) -> f64 {
+        (0..dimensions[0] - 1)
+            .map(|i| 100.0 * ((p[i + 1] - p[i]).powf(2.0)).powf(2.0) + (1.0 - p[i]).powf(2.0))
+            .sum()
+    }
+
+    let config = |initial_positions| Config {
+        dimensions: vec![3],
+        bounds: vec![(-5.0, 10.0); 3],
+        population_size: 20,
+        initial_positions,
+        t_max: 200,
+        progress_bar: false,
+        seed: Some(25),
+        ..Config::default()
+    };
+
+    // the minimum is given as the first particle, and the rest are sampled as usual
+    let sampled = pso_rs::init(config(vec![]), rosenbrock).unwrap();
+    let pso = pso_rs::init(config(vec![vec![1.0; 3], vec![2.0, -1.0, 0.5]]), rosenbrock).unwrap();
+    assert_eq!(pso.model.population[0], [1.0; 3]);
+    assert_eq!(pso.model.population[1], [2.0, -1.0, 0.5]);
+    for i in 2..20 {
+        assert_eq!(pso.model.population[i], sampled.model.population[i]);
+    }
+
+    // the given positions are the personal bests of their particles
+    assert_eq!(pso.model.get_f_best(), 0.0);
+    assert_eq!(pso.model.get_x_best(), vec![1.0; 3]);
+    assert_eq!(pso.best_f_values[0], 0.0);
+    assert_eq!(pso.neigh_population[0], [1.0; 3]);
+    assert_eq!(pso.best_f_values[1], pso.model.population_f_scores[1]);
+
+    let pso = pso_rs::run(config(vec![vec![1.0; 3]]), rosenbrock, None).unwrap();
+    assert_eq!(pso.model.get_f_best(), 0.0);
+
+    // with opposition-based initialization, only the sampled particles compete with their opposites, so even a poor given position is kept
+    let warm_start = vec![-5.0, 10.0, -5.0];
+    let pso = pso_rs::init(
+        Config {
+            initialization: Initialization::OppositionBased,
+            ..config(vec![warm_start.clone()])
+        },
+        rosenbrock,
+    )
+    .unwrap();
+    assert_eq!(pso.model.population[0], warm_start[..]);
+    assert_eq!(
+        pso.model.population_f_scores[0],
+        rosenbrock(&warm_start, 3, &vec![3])
+    );
+    assert_eq!(pso.neigh_population[0], warm_start[..]);
+    // the given position is evaluated once, and every sampled particle twice
+    assert_eq!(pso.evaluations(), 20 + 19);
+
+    for initial_positions in [
+        vec![vec![0.0; 3]; 21],
+        vec![vec![0.0; 2]],
+        vec![vec![0.0, 0.0, 11.0]],
+    ] {
+        match pso_rs::init(config(initial_positions), rosenbrock) {
+            Err(PsoError::InvalidConfig { field, .. }) => assert_eq!(field, "initial_positions"),
+            _ => panic!("expected an invalid config error"),
+        }
+    }
+}